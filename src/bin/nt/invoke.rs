@@ -1,6 +1,9 @@
-use clap::{Args, Parser, Subcommand};
+use std::path::PathBuf;
 
-use ntlib::LabelStringList;
+use chrono::NaiveDate;
+use clap::{ArgGroup, Args, Parser, Subcommand};
+
+use ntlib::{Chart, DigestFormat, Filters, GroupBy, LabelStringList, SortBy, TableOptions};
 
 /// Nu Tracker: Track W3C actions and horizontal review requests
 #[derive(Parser)]
@@ -11,9 +14,14 @@ pub struct Cli {
 	/// Verbose mode (prints out the 'gh' command line etc.)
 	#[arg(short, long)]
 	pub verbose: bool,
-	/// Operate from the perspective of WG (overrides config file)
+	/// Operate from the perspective of WG (overrides config file); the 'issues' and 'actions'
+	/// sub-commands accept a comma-separated list of WGs
 	#[arg(short = 'g', long, value_name = "WG")]
 	pub working_group: Option<String>,
+	/// Don't ask questions on first run: create the default config directory, and use the WG
+	/// given via --working-group as the default (also set via NT_NONINTERACTIVE)
+	#[arg(short = 'y', long)]
+	pub yes: bool,
 }
 
 #[derive(Subcommand)]
@@ -30,11 +38,31 @@ pub enum Command {
 	Actions {
 		#[clap(flatten)]
 		issue_action_args: IssueActionArgs,
+		#[clap(flatten)]
+		table: TableArgs,
+		/// Show a table, with subtotals, for each tf, repo or assignee
+		#[arg(long, value_name = "KEY")]
+		group_by: Option<GroupBy>,
+		/// Open all listed actions in your browser
+		#[arg(long)]
+		open: bool,
+		/// Instead of a table, list the actions as in the "Summary of action items" in minutes
+		/// (those created today are marked as new)
+		#[arg(long, conflicts_with = "group_by")]
+		minutes_summary: bool,
+		/// With --minutes-summary: list one action per line, to paste into IRC
+		#[arg(long, requires = "minutes_summary")]
+		irc: bool,
 	},
 	/// List spec review requests by due date, or open a specific request
 	Specs {
 		/// Review number (only) to open in the browser (e.g. '42')
 		review_number: Option<u32>,
+		#[clap(flatten)]
+		table: TableArgs,
+		/// Open all listed requests in your browser
+		#[arg(long, conflicts_with = "review_number")]
+		open: bool,
 	},
 	/// List requests for comments on other groups' issues
 	Comments {
@@ -49,29 +77,307 @@ pub enum Command {
 		source: bool,
 		/// Request number (only) to open in the browser (e.g. '42')
 		request_number: Option<u32>,
+		#[clap(flatten)]
+		table: TableArgs,
+		/// Open all listed requests in your browser
+		#[arg(long, conflicts_with = "request_number")]
+		open: bool,
+	},
+	/// List your actions, spec reviews and comment requests across all known groups, by due date
+	Mine {
+		#[clap(flatten)]
+		table: TableArgs,
+	},
+	/// List things to tidy up: unassigned, undated, long-overdue and stale items
+	Hygiene {
+		/// Issues with no activity in this many days are stale
+		#[arg(long, value_name = "DAYS", default_value_t = 30)]
+		stale_days: u64,
+		/// Actions more than this many days overdue are listed
+		#[arg(long, value_name = "DAYS", default_value_t = 14)]
+		overdue_days: u64,
+	},
+	/// Report what's changed since the last snapshot (or a given date), then store a new snapshot
+	Digest {
+		/// Compare against the latest snapshot from on or before this date (e.g. '2024-01-01'), or
+		/// search for items created or closed since then if there isn't one
+		#[arg(long, value_name = "DATE")]
+		since: Option<NaiveDate>,
+		/// Output format: markdown or email (plain text, with a subject line)
+		#[arg(long, value_name = "FORMAT", default_value = "markdown")]
+		format: DigestFormat,
+		/// Don't store a snapshot of how things are now
+		#[arg(long)]
+		no_save: bool,
 	},
-	/// Open a specific GitHub issue in your browser
+	/// Report how quickly spec review and comment requests have been closed
+	Stats {
+		/// Only count requests closed on or after this date (e.g. '2024-01-01')
+		#[arg(long, value_name = "DATE")]
+		since: Option<NaiveDate>,
+		/// How many of the most recently updated closed requests of each kind to count
+		#[arg(long, value_name = "COUNT", default_value_t = 200)]
+		limit: u32,
+		/// Output CSV instead of tables
+		#[arg(long)]
+		csv: bool,
+	},
+	/// Chart overdue actions' ages, or open comment requests over time
+	Chart {
+		/// Which chart: aging (open actions by weeks overdue) or comments (open comment requests
+		/// by week)
+		chart: Chart,
+		/// Save the chart as an SVG file, instead of showing a sparkline
+		#[arg(long, value_name = "FILE")]
+		svg: Option<PathBuf>,
+		/// How many weeks to cover (comments chart)
+		#[arg(long, value_name = "COUNT", default_value_t = 26)]
+		weeks: u32,
+		/// How many open, and how many recently closed, comment requests to count (comments chart)
+		#[arg(long, value_name = "COUNT", default_value_t = 200)]
+		limit: u32,
+	},
+	/// Remind assignees about actions and spec reviews that are due soon
+	///
+	/// Posted reminders are recorded in the config dir, so nobody is reminded twice about the same
+	/// due date.
+	Remind {
+		/// Remind about items due within this many days
+		#[arg(long, value_name = "DAYS", default_value_t = 3)]
+		days: u64,
+		/// Post reminders as comments on the issues (otherwise, they're printed, to copy and paste)
+		#[arg(long)]
+		post: bool,
+		/// Record printed reminders as sent (e.g. once you've pasted them)
+		#[arg(long, conflicts_with = "post")]
+		record: bool,
+		/// Show what would be done, without posting or recording anything
+		#[arg(long)]
+		dry_run: bool,
+	},
+	/// Serve actions, spec reviews and comment requests as JSON (or Atom feeds), e.g. for a
+	/// dashboard
+	///
+	/// The endpoints are /actions, /specs and /comments (add '.atom' for a feed). Their query
+	/// parameters are named after the corresponding sub-commands' long options (e.g.
+	/// '/actions?tf&assignee=alice&label=aria&updated-since=2024-01-01').
+	Serve {
+		/// Port to listen on
+		#[arg(long, default_value_t = 8080)]
+		port: u16,
+		/// Address to listen on (use '0.0.0.0' to accept connections from other machines)
+		#[arg(long, value_name = "ADDRESS", default_value = "127.0.0.1")]
+		bind: String,
+		/// Re-use results from GitHub for this many seconds
+		#[arg(long, value_name = "SECONDS", default_value_t = 300)]
+		cache_secs: u64,
+		/// Serve items from 'actions.json', 'specs.json' and 'comments.json' in DIR instead of
+		/// querying GitHub (the files are in the same format as the JSON that's served)
+		#[arg(long, value_name = "DIR")]
+		fixtures: Option<PathBuf>,
+	},
+	/// Export dated actions and spec review due dates as an iCalendar file
+	Calendar {
+		/// File to write the calendar to (e.g. 'nt.ics')
+		#[arg(short, long, value_name = "FILE")]
+		out: PathBuf,
+		/// Only those assigned to USER (use '@me' for yourself)
+		#[arg(short = 'u', long, value_name = "USER")]
+		assignee: Option<String>,
+	},
+	/// Open GitHub issues, PRs, repos or label pages in your browser
 	Browse {
-		/// Issue to open (e.g. 'w3c/apa#42')
-		issue_locator: String,
+		/// Issues or PRs ('w3c/apa#42', a GitHub URL, or shorthand that's resolved against the WG's
+		/// main repo: 'aria#42' for a repo with the same owner, or '#42'), or repos ('w3c/apa' or
+		/// 'aria')
+		#[arg(required_unless_present = "label")]
+		targets: Vec<String>,
+		/// Open the given repos' (or the WG's main repo's) page for this label
+		#[arg(short, long)]
+		label: Option<String>,
+	},
+	/// Show an issue's or PR's details and latest comments in the terminal
+	#[command(group(ArgGroup::new("item").required(true).args(["issue_locator", "review", "request"])))]
+	Show {
+		/// Issue or PR to show, in any of the forms accepted by 'browse'
+		issue_locator: Option<String>,
+		/// Show this spec review request, by number (e.g. '42')
+		#[arg(long, value_name = "NUMBER")]
+		review: Option<u32>,
+		/// Show this comment request, by number (e.g. '42')
+		#[arg(long, value_name = "NUMBER")]
+		request: Option<u32>,
+		/// How many of the latest comments to show
+		#[arg(short = 'n', long, value_name = "COUNT", default_value_t = 3)]
+		comments: usize,
+	},
+	/// Work with meeting minutes
+	Minutes {
+		#[command(subcommand)]
+		command: MinutesCommand,
 	},
 	/// Manage settings
 	Config {
 		#[command(subcommand)]
 		command: ConfigCommand,
 	},
+	/// Save, list and run named views (saved invocations of other sub-commands)
+	View {
+		#[command(subcommand)]
+		command: ViewCommand,
+	},
+}
+
+#[derive(Debug, Subcommand)]
+pub enum MinutesCommand {
+	/// Find the actions in minutes (HTML from scribe.perl, or an IRC log), and report those that
+	/// were never filed
+	Import {
+		/// Minutes file ('.html'/'.htm' files are treated as HTML; others as plain text)
+		file: PathBuf,
+		/// Date of the meeting, from which relative due dates are worked out [default: from the
+		/// minutes' URL or title, or today; this must be found or given to use --create]
+		#[arg(long, value_name = "DATE")]
+		date: Option<NaiveDate>,
+		/// File the actions that weren't filed already (you'll be asked first, unless using
+		/// --yes)
+		#[arg(long)]
+		create: bool,
+		/// Repo to file actions in [default: the WG's main repo]
+		#[arg(long, value_name = "REPO")]
+		repo: Option<String>,
+	},
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ViewCommand {
+	/// Save a view (e.g. 'nt view save rq -- actions -t rq adapt -m --assignee @me')
+	Save {
+		/// Name of the view
+		name: String,
+		/// The arguments to run nt with (after '--')
+		#[arg(last = true, required = true)]
+		args: Vec<String>,
+	},
+	/// Run a saved view
+	Run {
+		/// Name of the view
+		name: String,
+	},
+	/// List saved views
+	List,
+	/// Remove a saved view
+	Remove {
+		/// Name of the view
+		name: String,
+	},
 }
 
 #[derive(Debug, Subcommand)]
 pub enum ConfigCommand {
 	/// Show the default configuration directory path (without creating it)
 	ShowDir,
+	/// Show where the config files are, and the default WG
+	Show {
+		/// Also show project file and environment overrides, and where each value came from
+		#[arg(long)]
+		effective: bool,
+	},
+	/// Check the repos file for problems (e.g. typos, or malformed repo names)
+	Check,
+	/// Merge your repos file with the latest shipped version, keeping your additions
+	UpgradeRepos {
+		/// Show what would change, without saving anything
+		#[arg(long)]
+		dry_run: bool,
+	},
 	/// Get or set the default working group
 	WorkingGroup {
 		/// Operate from the perspective of WG (defaults to 'apa')
 		#[arg(value_name = "WG")]
 		working_group: Option<String>,
 	},
+	/// Propose repo info for a WG from W3C group/repository data (e.g. validate-repos' report.json)
+	Discover {
+		/// Short name of the WG (e.g. 'apa')
+		wg: String,
+		/// Local JSON file containing the group/repository data
+		#[arg(short, long, value_name = "FILE")]
+		from: PathBuf,
+		/// Add the proposed WG info to the repos file
+		#[arg(long)]
+		save: bool,
+	},
+	/// Edit the WGs, TFs and repos in the repos file
+	Repos {
+		#[command(subcommand)]
+		command: ReposCommand,
+	},
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ReposCommand {
+	/// Add a WG, with its main repo
+	AddWg {
+		/// Short name of the WG (e.g. 'apa')
+		wg: String,
+		/// Main repo for the WG (e.g. 'w3c/apa')
+		main: String,
+		#[clap(flatten)]
+		check: RepoCheckArgs,
+	},
+	/// Add a TF, with its main repo, to a WG
+	AddTf {
+		/// Short name of the WG (e.g. 'apa')
+		wg: String,
+		/// Short name of the TF (e.g. 'rq')
+		tf: String,
+		/// Main repo for the TF (e.g. 'w3c/rqtf')
+		main: String,
+		#[clap(flatten)]
+		check: RepoCheckArgs,
+	},
+	/// Add an "other" repo to a WG or TF
+	AddRepo {
+		/// Short name of the WG (e.g. 'apa')
+		wg: String,
+		/// Repo to add (e.g. 'w3c/raur')
+		repo: String,
+		/// Add the repo to this TF, rather than the WG
+		#[arg(short, long)]
+		tf: Option<String>,
+		#[clap(flatten)]
+		check: RepoCheckArgs,
+	},
+	/// Remove an "other" repo from a WG or TF
+	RemoveRepo {
+		/// Short name of the WG (e.g. 'apa')
+		wg: String,
+		/// Repo to remove (e.g. 'w3c/raur')
+		repo: String,
+		/// Remove the repo from this TF, rather than the WG
+		#[arg(short, long)]
+		tf: Option<String>,
+	},
+	/// Set the main repo of a WG or TF
+	SetMain {
+		/// Short name of the WG (e.g. 'apa')
+		wg: String,
+		/// New main repo (e.g. 'w3c/apa')
+		repo: String,
+		/// Set the main repo of this TF, rather than the WG
+		#[arg(short, long)]
+		tf: Option<String>,
+		#[clap(flatten)]
+		check: RepoCheckArgs,
+	},
+}
+
+#[derive(Debug, Args)]
+pub struct RepoCheckArgs {
+	/// Don't check (via 'gh') that the repo exists
+	#[arg(long)]
+	pub skip_check: bool,
 }
 
 #[derive(Args)]
@@ -87,6 +393,75 @@ pub struct IssueActionArgs {
 	/// Only those assigned to USER (use '@me' for yourself)
 	#[arg(short = 'u', long, value_name = "USER")]
 	pub assignee: Option<String>,
+	/// Query all known WGs (overrides --working-group)
+	#[arg(long)]
+	pub all_groups: bool,
+	#[clap(flatten)]
+	pub filters: FilterArgs,
+}
+
+#[derive(Args)]
+pub struct TableArgs {
+	/// Sort by KEY: due, repo, assignee, number or updated
+	#[arg(long, value_name = "KEY")]
+	pub sort: Option<SortBy>,
+	/// Reverse the order of the results
+	#[arg(long)]
+	pub reverse: bool,
+	/// Show these columns, in this order (e.g. 'due,locator,title')
+	#[arg(long, value_name = "LIST", value_delimiter = ',')]
+	pub columns: Option<Vec<String>>,
+}
+
+impl From<TableArgs> for TableOptions {
+	fn from(args: TableArgs) -> Self {
+		TableOptions {
+			sort: args.sort,
+			reverse: args.reverse,
+			columns: args.columns,
+		}
+	}
+}
+
+#[derive(Args)]
+pub struct FilterArgs {
+	/// Only those with LABEL (may be given more than once)
+	#[arg(short, long, value_name = "LABEL")]
+	pub label: Vec<String>,
+	/// Only those in the milestone with this title
+	#[arg(long, value_name = "TITLE")]
+	pub milestone: Option<String>,
+	/// Only those created by USER (use '@me' for yourself)
+	#[arg(long, value_name = "USER")]
+	pub author: Option<String>,
+	/// Only those that mention USER (use '@me' for yourself)
+	#[arg(long, value_name = "USER")]
+	pub mentions: Option<String>,
+	/// Only those matching TEXT (uses GitHub's search syntax)
+	#[arg(short, long, value_name = "TEXT")]
+	pub search: Option<String>,
+	/// Only those updated on or after DATE (e.g. '2023-05-23')
+	#[arg(long, value_name = "DATE")]
+	pub updated_since: Option<NaiveDate>,
+	/// Only those created on or after DATE (e.g. '2023-05-23')
+	#[arg(long, value_name = "DATE")]
+	pub created_since: Option<NaiveDate>,
+}
+
+impl From<FilterArgs> for Filters {
+	fn from(args: FilterArgs) -> Self {
+		Filters {
+			labels: args.label,
+			milestone: args.milestone,
+			author: args.author,
+			mentions: args.mentions,
+			search: args.search,
+			updated_since: args.updated_since,
+			created_since: args.created_since,
+			closed_since: None,
+			limit: None,
+		}
+	}
 }
 
 #[derive(Args)]
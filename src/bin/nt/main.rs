@@ -1,67 +1,159 @@
-use std::str::FromStr;
+use std::io::{self, Write};
+use std::{collections::BTreeMap, fs, path::Path, time::Duration};
 
+use chrono::Local;
 use clap::Parser;
 
-use ntlib::{actions, comments, config, issues, specs, FlagLabelMap, LabelStringList, Locator};
+use ntlib::config::WorkingGroupInfo;
+use ntlib::{
+	actions, calendar, chart, comments, config, digest, file_actions, find_meeting_date, hygiene,
+	import_minutes, issues, mine, remind, serve, show, specs, stats, Backend, FlagLabelMap,
+	Locator, Page, SummaryStyle,
+};
 
 mod invoke;
 
-use crate::invoke::{Cli, Command, ConfigCommand};
+use crate::invoke::{
+	Cli, Command, ConfigCommand, MinutesCommand, RepoCheckArgs, ReposCommand, ViewCommand,
+};
+
+/// More than this many pages are only opened if the user agrees
+const OPEN_WITHOUT_ASKING: usize = 10;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-	let cli = Cli::parse();
+	let mut cli = Cli::parse();
+
+	// When creating the settings file non-interactively, the default WG comes from -g or NT_WG
+	let env_wg = config::env_wg();
+	let prompting =
+		config::Prompting::new(cli.yes, cli.working_group.as_deref().or(env_wg.as_deref()));
+	config::ensure_dir(prompting)?;
+
+	// This needs to happen before the repos file is loaded, as loading will fail on errors.
+	if let Command::Config {
+		command: ConfigCommand::Check,
+	} = cli.command
+	{
+		return check_repos();
+	}
+
+	// Upgrading may be needed precisely because the current file can't be loaded.
+	if let Command::Config {
+		command: ConfigCommand::UpgradeRepos { dry_run },
+	} = cli.command
+	{
+		return upgrade_repos(dry_run);
+	}
 
-	config::ensure_dir()?;
 	let repositories = config::Repos::load_or_init()?;
-	let settings = config::Settings::load_or_init()?;
+	let settings = config::Settings::load_or_init(&repositories.known_wg_names(), prompting)?;
+	if let Command::View {
+		command: ViewCommand::Run { name },
+	} = &cli.command
+	{
+		cli = view_cli(&cli, name, settings.views())?;
+	}
+
+	let project = config::ProjectConfig::find()?;
+	let effective = config::Effective::new(
+		&repositories,
+		settings.wg(),
+		project.as_ref(),
+		config::env_wg(),
+	)?;
 
-	let group_name = ascertain_group_name(
+	let group_names = ascertain_group_names(
 		&cli.working_group,
-		|| settings.wg().to_string(),
-		&repositories.known_wg_names(),
+		&effective.working_group,
+		&effective.repos.known_wg_names(),
 	);
+	let group_name = group_names[0].clone();
+
+	if group_names.len() > 1
+		&& !matches!(
+			cli.command,
+			Command::Issues { .. } | Command::Actions { .. }
+		) {
+		println!("Only the 'issues' and 'actions' sub-commands support multiple WGs - using '{group_name}' for this run.")
+	}
 
 	if cli.verbose {
-		println!("Operating from the perspective of the '{}' WG", group_name)
+		println!(
+			"Operating from the perspective of the '{}' WG(s)",
+			group_names.join("', '")
+		)
 	}
 
-	let all_wgs_repos = repositories.wgs_repos();
+	let all_wgs_repos = effective.repos.wgs_repos();
 	let wg_repos = all_wgs_repos
 		.get(&group_name)
 		.expect("should be able to get WorkingGroupInfo");
 
+	let interactive = matches!(
+		config::Prompting::new(cli.yes, None),
+		config::Prompting::Interactive
+	);
+
 	match cli.command {
 		Command::Issues {
 			actions,
 			issue_action_args,
 		} => issues(
-			wg_repos,
+			&selected_groups(all_wgs_repos, &group_names, &issue_action_args.all_groups),
 			&actions,
 			&issue_action_args.assignee,
 			&issue_action_args.closed,
 			&issue_action_args.main,
 			&issue_action_args.sources.wg,
 			&issue_action_args.sources.tf,
+			&issue_action_args.filters.into(),
 			&cli.verbose,
 		),
 
-		Command::Actions { issue_action_args } => actions(
-			wg_repos,
-			&issue_action_args.assignee,
-			&issue_action_args.closed,
-			&issue_action_args.main,
-			&issue_action_args.sources.wg,
-			&issue_action_args.sources.tf,
-			&cli.verbose,
-		),
+		Command::Actions {
+			issue_action_args,
+			table,
+			group_by,
+			open,
+			minutes_summary,
+			irc,
+		} => {
+			let summary = match (minutes_summary, irc) {
+				(false, _) => None,
+				(true, false) => Some(SummaryStyle::Minutes),
+				(true, true) => Some(SummaryStyle::Irc),
+			};
+			let listed = actions(
+				&selected_groups(all_wgs_repos, &group_names, &issue_action_args.all_groups),
+				&issue_action_args.assignee,
+				&issue_action_args.closed,
+				&issue_action_args.main,
+				&issue_action_args.sources.wg,
+				&issue_action_args.sources.tf,
+				&issue_action_args.filters.into(),
+				&table.into(),
+				&group_by,
+				&summary,
+				&cli.verbose,
+			);
+			if open {
+				open_all(&listed, interactive)
+			}
+		}
 
-		Command::Specs { review_number } => {
+		Command::Specs {
+			review_number,
+			table,
+			open,
+		} => {
+			let table_options = table.into();
 			// FIXME: this is already checked in comments() and specs() -- somehow enforce that only Some() variants are passed in?
-			if wg_repos.horizontal_review.is_some() {
+			if let Some(horizontal_review) = &wg_repos.horizontal_review {
 				comments_or_specs(
-					|| specs(&group_name, wg_repos, &cli.verbose),
+					|| specs(&group_name, wg_repos, &table_options, &cli.verbose),
 					&review_number,
-					&wg_repos.horizontal_review.as_ref().unwrap().specs,
+					&horizontal_review.specs,
+					open.then_some(interactive),
 				)
 			} else {
 				println!("{group_name} is not a horizontal review group")
@@ -73,47 +165,244 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 			source,
 			request_number,
 			status,
+			table,
+			open,
 		} => {
 			if status_flags {
 				println!("{}", FlagLabelMap::new());
 				return Ok(());
 			}
 
+			let table_options = table.into();
 			// FIXME: this is already checked in comments() and specs() -- somehow enforce that only Some() variants are passed in?
-			if wg_repos.horizontal_review.is_some() {
+			if let Some(horizontal_review) = &wg_repos.horizontal_review {
 				comments_or_specs(
 					|| {
 						comments(
 							&group_name,
 							wg_repos,
-							// TODO: implement Default (needs cleanup)
 							// TODO: clean up generally
-							status
-								.as_ref()
-								.unwrap_or(LabelStringList::from_str("").as_ref().unwrap()),
+							&status.clone().unwrap_or_default(),
 							&source,
+							&table_options,
 							&cli.verbose,
 						)
 					},
 					&request_number,
-					&wg_repos.horizontal_review.as_ref().unwrap().comments,
+					&horizontal_review.comments,
+					open.then_some(interactive),
 				)
 			} else {
 				println!("{group_name} is not a horizontal review group")
 			}
 		}
 
-		Command::Browse { issue_locator } => open_locator(&issue_locator),
+		Command::Mine { table } => mine(&effective.repos, &table.into(), &cli.verbose),
+
+		Command::Hygiene {
+			stale_days,
+			overdue_days,
+		} => hygiene(
+			&group_name,
+			wg_repos,
+			stale_days,
+			overdue_days,
+			&cli.verbose,
+		),
+
+		Command::Digest {
+			since,
+			format,
+			no_save,
+		} => digest(
+			&group_name,
+			wg_repos,
+			&since,
+			format,
+			!no_save,
+			&cli.verbose,
+		)?,
+
+		Command::Stats { since, limit, csv } => {
+			stats(&group_name, wg_repos, since, limit, csv, &cli.verbose)
+		}
+
+		Command::Chart {
+			chart: which,
+			svg,
+			weeks,
+			limit,
+		} => chart(
+			&group_name,
+			wg_repos,
+			which,
+			weeks,
+			limit,
+			&svg.as_deref(),
+			&cli.verbose,
+		)?,
+
+		Command::Remind {
+			days,
+			post,
+			record,
+			dry_run,
+		} => remind(
+			&group_name,
+			wg_repos,
+			days,
+			post,
+			record,
+			dry_run,
+			&cli.verbose,
+		)?,
+
+		Command::Serve {
+			port,
+			bind,
+			cache_secs,
+			fixtures,
+		} => {
+			let backend = match fixtures {
+				Some(dir) => Backend::Fixtures(dir),
+				None => Backend::GitHub(Duration::from_secs(cache_secs)),
+			};
+			serve(&group_name, wg_repos, &bind, port, backend, &cli.verbose)?
+		}
+
+		Command::Calendar { out, assignee } => {
+			calendar(&group_name, wg_repos, &assignee, &out, &cli.verbose)?
+		}
+
+		Command::Show {
+			issue_locator,
+			review,
+			request,
+			comments,
+		} => {
+			let locator = if let Some(issue_locator) = issue_locator {
+				Locator::resolve(&issue_locator, &wg_repos.working_group.main)
+					.map_err(|_| format!("Invalid issue locator: {issue_locator}"))
+			} else if let Some(horizontal_review) = &wg_repos.horizontal_review {
+				let (repo, number) = match (review, request) {
+					(Some(number), _) => (&horizontal_review.specs, number),
+					(_, Some(number)) => (&horizontal_review.comments, number),
+					_ => unreachable!("clap requires an issue locator, review or request"),
+				};
+				Locator::from_name_with_owner(repo, number)
+					.map_err(|_| format!("Invalid number: {number}"))
+			} else {
+				Err(format!("{group_name} is not a horizontal review group"))
+			};
+			match locator {
+				Ok(locator) => show(&locator, comments, &cli.verbose),
+				Err(message) => println!("{message}"),
+			}
+		}
+
+		Command::Browse { targets, label } => {
+			let main_repo = &wg_repos.working_group.main;
+			let targets = if targets.is_empty() {
+				vec![main_repo.to_string()]
+			} else {
+				targets
+			};
+
+			let mut pages: Vec<Page> = vec![];
+			for target in targets {
+				match Page::resolve(&target, label.as_deref(), main_repo) {
+					Ok(page) => pages.push(page),
+					Err(_) => {
+						println!("Invalid issue locator or repo: {target}");
+						return Ok(());
+					}
+				}
+			}
+
+			let urls: Vec<String> = pages.iter().map(Page::url).collect();
+			open_urls(&urls, interactive)
+		}
+
+		Command::Minutes {
+			command: MinutesCommand::Import {
+				file,
+				date,
+				create,
+				repo,
+			},
+		} => {
+			let minutes = fs::read_to_string(&file)?;
+			let is_html = file
+				.extension()
+				.is_some_and(|extension| extension == "html" || extension == "htm");
+			// Actions already filed are found by creation date, so filing them depends on it.
+			let meeting_date = match date.or_else(|| find_meeting_date(&minutes)) {
+				Some(date) => date,
+				None if create => {
+					return Err(
+						"Can't find the meeting date in the minutes; give it with --date".into(),
+					)
+				}
+				None => {
+					println!("Can't find the meeting date in the minutes; using today's (give it with --date)\n");
+					Local::now().date_naive()
+				}
+			};
+			let not_filed = import_minutes(
+				&group_name,
+				wg_repos,
+				&minutes,
+				is_html,
+				meeting_date,
+				&cli.verbose,
+			);
+
+			if create && !not_filed.is_empty() {
+				let repo = repo.unwrap_or(wg_repos.working_group.main.to_string());
+				if !interactive
+					|| confirm(&format!(
+						"File {} actions in {repo}? [y/N]",
+						not_filed.len()
+					)) {
+					file_actions(&repo, &not_filed, &file.to_string_lossy(), &cli.verbose)?
+				}
+			}
+		}
 
 		Command::Config { command } => match command {
 			ConfigCommand::ShowDir => println!("{}", config::config_dir().to_string_lossy()),
 
+			ConfigCommand::Show {
+				effective: show_effective,
+			} => show_config(
+				&effective,
+				project.as_ref(),
+				&cli.working_group,
+				show_effective,
+			),
+
+			ConfigCommand::Repos { command } => edit_repos(repositories, command)?,
+
+			ConfigCommand::Discover { wg, from, save } => {
+				discover_repos(repositories, &wg, &from, save)?
+			}
+
+			ConfigCommand::Check | ConfigCommand::UpgradeRepos { .. } => {
+				unreachable!("should've been handled before loading config files")
+			}
+
 			ConfigCommand::WorkingGroup { working_group } => {
 				let mut settings = settings;
 				match working_group {
 					Some(wg) => settings.set_wg(wg, &repositories.known_wg_names()),
 					None => {
 						println!("Default WG is: '{}'", settings.wg());
+						if effective.working_group.1 != config::Source::SettingsFile {
+							println!(
+								"...but '{}' is in use, from the {}.",
+								effective.working_group.0, effective.working_group.1
+							)
+						}
 						println!(
 							"You can override this temporarily via the --working-group/-g option."
 						)
@@ -121,45 +410,402 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 				}
 			}
 		},
+
+		Command::View { command } => manage_views(settings, command)?,
 	}
 
 	Ok(())
 }
 
-fn comments_or_specs<F: Fn()>(handler: F, open_number: &Option<u32>, org_and_repo: &str) {
-	if let Some(targ) = open_number {
-		let locator = format!("{org_and_repo}#{targ}");
-		open_locator(locator.as_str())
+fn show_config(
+	effective: &config::Effective,
+	project: Option<&config::ProjectConfig>,
+	working_group: &Option<String>,
+	show_effective: bool,
+) {
+	let dir = config::config_dir();
+	println!(
+		"Config dir: {:?} (from {})",
+		dir,
+		config::config_dir_source()
+	);
+	println!("Repos file: {:?}", dir.join(config::Repos::file_name()));
+	println!(
+		"Settings file: {:?}",
+		dir.join(config::Settings::file_name())
+	);
+
+	if !show_effective {
+		println!("Default WG: '{}'", effective.working_group.0);
+		return;
+	}
+
+	match project {
+		Some(project) => println!("Project file: {:?}", project.path()),
+		None => println!(
+			"Project file: none ({} not found in this directory or its parents)",
+			config::PROJECT_FILE_NAME
+		),
+	}
+
+	match working_group {
+		Some(wgs) => println!("WG(s): '{wgs}' (from {})", config::Source::CommandLine),
+		None => println!(
+			"Default WG: '{}' (from {})",
+			effective.working_group.0, effective.working_group.1
+		),
+	}
+
+	println!("Known WGs:");
+	for (wg, sources) in &effective.group_sources {
+		let sources: Vec<String> = sources.iter().map(ToString::to_string).collect();
+		println!("  {wg} (from {})", sources.join(", then "))
+	}
+}
+
+/// Parse the arguments from a saved view, keeping the global options given on this run
+fn view_cli(
+	cli: &Cli,
+	name: &str,
+	views: &BTreeMap<String, Vec<String>>,
+) -> Result<Cli, Box<dyn std::error::Error>> {
+	let args = views
+		.get(name)
+		.ok_or_else(|| format!("No view called '{name}' (use 'nt view list' to see them)"))?;
+	let mut view = parse_view(args)?;
+
+	view.verbose |= cli.verbose;
+	view.yes |= cli.yes;
+	if cli.working_group.is_some() {
+		view.working_group = cli.working_group.clone()
+	}
+
+	if cli.verbose {
+		println!("Running view '{name}': nt {}", args.join(" "))
+	}
+	Ok(view)
+}
+
+fn parse_view(args: &[String]) -> Result<Cli, Box<dyn std::error::Error>> {
+	let view = Cli::try_parse_from(std::iter::once("nt").chain(args.iter().map(String::as_str)))
+		.map_err(|error| {
+			let message = error.to_string();
+			let first_line = message.lines().next().unwrap_or_default();
+			format!("Invalid view: {}", first_line.trim_start_matches("error: "))
+		})?;
+	match view.command {
+		Command::View { .. } => Err("Views can't run the 'view' sub-command".into()),
+		// These are run before views are expanded, because they work when the repos file can't be
+		// loaded.
+		Command::Config {
+			command: ConfigCommand::Check | ConfigCommand::UpgradeRepos { .. },
+		} => Err("Views can't run 'config check' or 'config upgrade-repos'".into()),
+		_ => Ok(view),
+	}
+}
+
+fn manage_views(
+	mut settings: config::Settings,
+	command: ViewCommand,
+) -> Result<(), Box<dyn std::error::Error>> {
+	match command {
+		ViewCommand::Save { name, args } => {
+			parse_view(&args)?;
+			println!("Saved view '{name}': nt {}", args.join(" "));
+			settings.save_view(name, args)
+		}
+		ViewCommand::Run { .. } => unreachable!("views should've been expanded already"),
+		ViewCommand::List => {
+			if settings.views().is_empty() {
+				println!("No saved views (use 'nt view save' to add one)")
+			}
+			for (name, args) in settings.views() {
+				println!("{name}: nt {}", args.join(" "))
+			}
+		}
+		ViewCommand::Remove { name } => {
+			if settings.remove_view(&name) {
+				println!("Removed view '{name}'")
+			} else {
+				return Err(format!("No view called '{name}'").into());
+			}
+		}
+	}
+	Ok(())
+}
+
+fn check_repos() -> Result<(), Box<dyn std::error::Error>> {
+	let file_name = config::Repos::file_name();
+	if !config::config_dir().join(file_name).exists() {
+		println!("There's no {file_name} file yet; the default one will be saved on the next run.");
+		return Ok(());
+	}
+	let problems = config::Repos::check()?;
+
+	if problems.is_empty() {
+		println!("No problems found in {file_name}");
+		return Ok(());
+	}
+
+	for problem in &problems {
+		println!("{file_name}: {problem}")
+	}
+
+	if problems
+		.iter()
+		.any(|problem| problem.severity == config::Severity::Error)
+	{
+		Err(format!("{file_name} has errors").into())
 	} else {
-		handler()
+		Ok(())
 	}
 }
 
-fn open_locator(issue_locator: &str) {
-	if let Ok(locator) = Locator::from_str(issue_locator) {
-		println!("Opening: {}", locator.url());
-		if let Err(err) = open::that(locator.url()) {
-			println!("Error: {err}")
+fn edit_repos(
+	mut repositories: config::Repos,
+	command: ReposCommand,
+) -> Result<(), Box<dyn std::error::Error>> {
+	match command {
+		ReposCommand::AddWg { wg, main, check } => {
+			check_repo_exists(&main, &check)?;
+			repositories.add_wg(&wg, &main)?
 		}
+		ReposCommand::AddTf {
+			wg,
+			tf,
+			main,
+			check,
+		} => {
+			check_repo_exists(&main, &check)?;
+			repositories.add_tf(&wg, &tf, &main)?
+		}
+		ReposCommand::AddRepo {
+			wg,
+			repo,
+			tf,
+			check,
+		} => {
+			check_repo_exists(&repo, &check)?;
+			repositories.add_repo(&wg, tf.as_deref(), &repo)?
+		}
+		ReposCommand::RemoveRepo { wg, repo, tf } => {
+			repositories.remove_repo(&wg, tf.as_deref(), &repo)?
+		}
+		ReposCommand::SetMain {
+			wg,
+			repo,
+			tf,
+			check,
+		} => {
+			check_repo_exists(&repo, &check)?;
+			repositories.set_main(&wg, tf.as_deref(), &repo)?
+		}
+	}
+
+	save_repos(&repositories)
+}
+
+fn discover_repos(
+	mut repositories: config::Repos,
+	wg: &str,
+	from: &Path,
+	save: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+	let info = config::discover(wg, &fs::read_to_string(from)?)?;
+
+	println!(
+		"{}",
+		serde_json::to_string_pretty(&BTreeMap::from([(wg, &info)]))?
+	);
+
+	if save {
+		repositories.add_wg_info(wg, info)?;
+		save_repos(&repositories)
+	} else {
+		println!("\nTo add this to your repos file, use --save (and check the TFs are correct).");
+		Ok(())
+	}
+}
+
+fn save_repos(repositories: &config::Repos) -> Result<(), Box<dyn std::error::Error>> {
+	let file_name = config::Repos::file_name();
+	let problems = repositories.problems();
+	for problem in &problems {
+		println!("{file_name}: {problem}")
+	}
+	if problems
+		.iter()
+		.any(|problem| problem.severity == config::Severity::Error)
+	{
+		return Err(format!("not saving {file_name}, as it would have errors").into());
+	}
+
+	repositories.save();
+	println!("Saved {:?}", config::config_dir().join(file_name));
+	Ok(())
+}
+
+fn check_repo_exists(repo: &str, check: &RepoCheckArgs) -> Result<(), String> {
+	if check.skip_check || config::repo_exists(repo) {
+		Ok(())
 	} else {
-		println!("Invalid issue locator: {issue_locator}")
+		Err(format!(
+			"Can't find repo '{repo}' on GitHub (use --skip-check to add it anyway)"
+		))
+	}
+}
+
+fn upgrade_repos(dry_run: bool) -> Result<(), Box<dyn std::error::Error>> {
+	let report = config::Repos::upgrade(dry_run)?;
+
+	if report.changes.is_empty() && report.conflicts.is_empty() {
+		println!("No changes to {}", config::Repos::file_name());
+	}
+
+	for change in &report.changes {
+		println!("{change}")
+	}
+
+	for conflict in &report.conflicts {
+		println!("{conflict}")
+	}
+
+	if dry_run {
+		println!("\nDry run: nothing has been saved.")
+	} else {
+		println!(
+			"\nSaved {:?}",
+			config::config_dir().join(config::Repos::file_name())
+		)
+	}
+
+	Ok(())
+}
+
+/// Either open the given item, or run the handler, then open all listed items if `open_all_asking`
+/// is given (its value says whether to ask first, if there are many)
+fn comments_or_specs<F: Fn() -> Vec<Locator>>(
+	handler: F,
+	open_number: &Option<u32>,
+	org_and_repo: &str,
+	open_all_asking: Option<bool>,
+) {
+	if let Some(targ) = open_number {
+		match Locator::from_name_with_owner(org_and_repo, *targ) {
+			Ok(locator) => open_url(&locator.url()),
+			Err(_) => println!("Invalid issue locator: {org_and_repo}#{targ}"),
+		}
+	} else {
+		let listed = handler();
+		if let Some(ask) = open_all_asking {
+			open_all(&listed, ask)
+		}
+	}
+}
+
+fn open_all(locators: &[Locator], ask: bool) {
+	let urls: Vec<String> = locators.iter().map(Locator::url).collect();
+	open_urls(&urls, ask)
+}
+
+/// Open the pages, first asking if there are more than [`OPEN_WITHOUT_ASKING`] (unless told not to
+/// ask)
+fn open_urls(urls: &[String], ask: bool) {
+	if ask
+		&& urls.len() > OPEN_WITHOUT_ASKING
+		&& !confirm(&format!("Open {} pages in your browser? [y/N]", urls.len()))
+	{
+		return;
+	}
+
+	for url in urls {
+		open_url(url)
+	}
+}
+
+/// Ask a yes/no question, where the default is no
+fn confirm(prompt: &str) -> bool {
+	print!("{prompt} ");
+	let _ = io::stdout().flush();
+	let mut input = String::new();
+	let _ = io::stdin().read_line(&mut input);
+	matches!(input.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+fn open_url(url: &str) {
+	println!("Opening: {url}");
+	if let Err(err) = open::that(url) {
+		println!("Error: {err}")
+	}
+}
+
+fn selected_groups<'a>(
+	all_wgs_repos: &'a BTreeMap<String, WorkingGroupInfo>,
+	group_names: &'a [String],
+	all_groups: &bool,
+) -> Vec<(&'a str, &'a WorkingGroupInfo)> {
+	if *all_groups {
+		all_wgs_repos
+			.iter()
+			.map(|(name, info)| (name.as_str(), info))
+			.collect()
+	} else {
+		group_names
+			.iter()
+			.map(|name| {
+				(
+					name.as_str(),
+					all_wgs_repos
+						.get(name)
+						.expect("should be able to get WorkingGroupInfo"),
+				)
+			})
+			.collect()
+	}
+}
+
+/// Work out which WG(s) to use, from a comma-separated list given on the command line, or the
+/// effective default WG
+fn ascertain_group_names(
+	parameter: &Option<String>,
+	fallback: &(String, config::Source),
+	valid_wgs: &[&String],
+) -> Vec<String> {
+	match parameter {
+		Some(list) => {
+			let mut names: Vec<String> = vec![];
+			for name in list.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+				let name = name.to_string();
+				if !valid_wgs.contains(&&name) {
+					println!("Unknown WG name given on command line: '{name}' - ignoring it.");
+				} else if !names.contains(&name) {
+					names.push(name)
+				}
+			}
+			if names.is_empty() {
+				println!("No known WG names given on command line - using 'apa' for this run.");
+				names.push(String::from("apa"))
+			}
+			names
+		}
+		None => vec![ascertain_group_name(parameter, fallback, valid_wgs)],
 	}
 }
 
 fn ascertain_group_name(
 	parameter: &Option<String>,
-	fallback: impl Fn() -> String,
+	fallback: &(String, config::Source),
 	valid_wgs: &[&String],
 ) -> String {
 	let group_name = match parameter {
 		Some(group) => group.clone(), // TODO: why does this contain a &String?
-		None => fallback(),
+		None => fallback.0.clone(),
 	};
 
 	if !valid_wgs.contains(&&group_name) {
 		let qualifier = match parameter {
-			Some(_) => "given on command line",
-			None => "specified in settings file",
+			Some(_) => String::from("given on command line"),
+			None => format!("from the {}", fallback.1),
 		};
 		println!("Unknown WG name {qualifier}: '{group_name}' - using 'apa' for this run.");
 		return String::from("apa");
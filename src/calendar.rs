@@ -0,0 +1,241 @@
+use std::{fs, io, path::Path};
+
+use chrono::{DateTime, Days, NaiveDate, Utc};
+
+use crate::config::WorkingGroupInfo;
+use crate::filters::Filters;
+use crate::flatten_assignees::flatten_assignees;
+use crate::issues_actions::query_actions;
+use crate::locator::Locator;
+use crate::returned_issue::Assignee;
+use crate::specs::query_review_requests;
+
+const PRODUCT_ID: &str = "-//matatk//Nu Tracker//EN";
+const MAX_LINE_OCTETS: usize = 75;
+
+struct Event {
+	locator: Locator,
+	summary: String,
+	due: NaiveDate,
+	/// Assignees are attendees (with their GitHub profile URLs, as their email addresses aren't
+	/// known), and are also listed in the description, for clients that don't show such attendees
+	assignees: Vec<Assignee>,
+}
+
+impl Event {
+	fn description(&self) -> String {
+		if self.assignees.is_empty() {
+			self.locator.to_string()
+		} else {
+			format!(
+				"{} (assigned to {})",
+				self.locator,
+				flatten_assignees(&self.assignees).replace(',', ", ")
+			)
+		}
+	}
+
+	fn to_lines(&self, stamp: &DateTime<Utc>) -> Vec<String> {
+		let mut lines = vec![
+			String::from("BEGIN:VEVENT"),
+			format!("UID:{}@nu-tracker", self.locator),
+			format!("DTSTAMP:{}", stamp.format("%Y%m%dT%H%M%SZ")),
+			format!("DTSTART;VALUE=DATE:{}", self.due.format("%Y%m%d")),
+			format!(
+				"DTEND;VALUE=DATE:{}",
+				(self.due + Days::new(1)).format("%Y%m%d")
+			),
+			format!("SUMMARY:{}", escape_text(&self.summary)),
+			format!("DESCRIPTION:{}", escape_text(&self.description())),
+			format!("URL:{}", self.locator.url()),
+		];
+		for assignee in &self.assignees {
+			// GitHub logins don't contain characters that would need quoting.
+			lines.push(format!("ATTENDEE;CN={}:{}", assignee.login, assignee.url))
+		}
+		lines.push(String::from("END:VEVENT"));
+		lines
+	}
+}
+
+/// Write an iCalendar file containing all-day events for dated actions, and spec review requests.
+///
+/// Actions are taken from the WG's and all TFs' repos. Spec review requests are included if the
+/// group is a horizontal review group.
+pub fn calendar(
+	group_name: &str,
+	repos: &WorkingGroupInfo,
+	assignee: &Option<String>,
+	out: &Path,
+	verbose: &bool,
+) -> io::Result<()> {
+	let mut events: Vec<Event> = vec![];
+
+	for dated in query_actions(
+		&[(group_name, repos)],
+		assignee,
+		&false,
+		&false,
+		&true,
+		&Some(vec![]),
+		&Filters::everything(),
+		verbose,
+	) {
+		if let Some(due) = dated.due {
+			events.push(Event {
+				locator: Locator::from_name_with_owner(
+					&dated.action.repository.name_with_owner,
+					dated.action.number,
+				)
+				.expect("should be able to make a Locator from returned issue"),
+				summary: format!("Action: {}", dated.action.title),
+				due,
+				assignees: dated.action.assignees,
+			})
+		}
+	}
+
+	if let Some(horizontal_review) = &repos.horizontal_review {
+		for request in query_review_requests(&horizontal_review.specs, assignee, verbose) {
+			events.push(Event {
+				locator: Locator::from_name_with_owner(&horizontal_review.specs, request.number)
+					.expect("should be able to make a Locator from returned issue"),
+				summary: format!("Review: {}", request.spec),
+				due: request.due,
+				assignees: request.assignees,
+			})
+		}
+	} else if *verbose {
+		println!("Group '{group_name}' is not a horizontal review group; skipping spec reviews.")
+	}
+
+	fs::write(out, make_calendar(&events, &Utc::now()))?;
+	println!("Wrote {} events to: {:?}", events.len(), out);
+	Ok(())
+}
+
+fn make_calendar(events: &[Event], stamp: &DateTime<Utc>) -> String {
+	let mut lines = vec![
+		String::from("BEGIN:VCALENDAR"),
+		String::from("VERSION:2.0"),
+		format!("PRODID:{PRODUCT_ID}"),
+		String::from("CALSCALE:GREGORIAN"),
+	];
+
+	for event in events {
+		lines.extend(event.to_lines(stamp))
+	}
+
+	lines.push(String::from("END:VCALENDAR"));
+
+	let mut output = String::new();
+	for line in lines {
+		output.push_str(&fold_line(&line));
+		output.push_str("\r\n");
+	}
+	output
+}
+
+/// Escape TEXT property values (RFC 5545 section 3.3.11)
+fn escape_text(text: &str) -> String {
+	text.replace('\\', "\\\\")
+		.replace(';', "\\;")
+		.replace(',', "\\,")
+		.replace("\r\n", "\\n")
+		.replace('\n', "\\n")
+}
+
+/// Fold content lines longer than 75 octets (RFC 5545 section 3.1), without splitting characters
+fn fold_line(line: &str) -> String {
+	let mut folded = String::new();
+	let mut octets = 0;
+
+	for character in line.chars() {
+		let length = character.len_utf8();
+		if octets + length > MAX_LINE_OCTETS {
+			folded.push_str("\r\n ");
+			octets = 1;
+		}
+		folded.push(character);
+		octets += length;
+	}
+
+	folded
+}
+
+#[cfg(test)]
+mod tests {
+	use std::str::FromStr;
+
+	use chrono::TimeZone;
+
+	use super::*;
+
+	#[test]
+	fn escaping() {
+		assert_eq!(
+			escape_text("One, two; three\\four\nfive"),
+			String::from("One\\, two\\; three\\\\four\\nfive")
+		)
+	}
+
+	#[test]
+	fn short_line_not_folded() {
+		assert_eq!(fold_line("SUMMARY:Hello"), String::from("SUMMARY:Hello"))
+	}
+
+	#[test]
+	fn long_line_folded() {
+		let folded = fold_line(&"x".repeat(160));
+		let lines: Vec<&str> = folded.split("\r\n").collect();
+		assert_eq!(lines.len(), 3);
+		assert_eq!(lines[0].len(), 75);
+		assert_eq!(lines[1].len(), 75);
+		assert!(lines[1].starts_with(' '));
+		assert_eq!(lines[2], format!(" {}", "x".repeat(11)));
+	}
+
+	#[test]
+	fn multi_byte_characters_not_split() {
+		let folded = fold_line(&"é".repeat(50));
+		for line in folded.split("\r\n") {
+			assert!(line.len() <= 75)
+		}
+	}
+
+	#[test]
+	fn calendar_event() {
+		let stamp = Utc.with_ymd_and_hms(2023, 6, 1, 12, 0, 0).unwrap();
+		let event = Event {
+			locator: Locator::from_str("w3c/apa#42").unwrap(),
+			summary: String::from("Action: Review the thing"),
+			due: NaiveDate::from_ymd_opt(2023, 6, 30).unwrap(),
+			assignees: serde_json::from_str(
+				r#"[
+					{ "id": "1", "is_bot": false, "login": "alice", "type": "User", "url": "https://github.com/alice" },
+					{ "id": "2", "is_bot": false, "login": "bob", "type": "User", "url": "https://github.com/bob" }
+				]"#,
+			)
+			.unwrap(),
+		};
+		assert_eq!(
+			make_calendar(&[event], &stamp),
+			"BEGIN:VCALENDAR\r\n\
+VERSION:2.0\r\n\
+PRODID:-//matatk//Nu Tracker//EN\r\n\
+CALSCALE:GREGORIAN\r\n\
+BEGIN:VEVENT\r\n\
+UID:w3c/apa#42@nu-tracker\r\n\
+DTSTAMP:20230601T120000Z\r\n\
+DTSTART;VALUE=DATE:20230630\r\n\
+DTEND;VALUE=DATE:20230701\r\n\
+SUMMARY:Action: Review the thing\r\n\
+DESCRIPTION:w3c/apa#42 (assigned to alice\\, bob)\r\n\
+URL:https://github.com/w3c/apa/issues/42\r\n\
+ATTENDEE;CN=alice:https://github.com/alice\r\n\
+ATTENDEE;CN=bob:https://github.com/bob\r\n\
+END:VEVENT\r\n\
+END:VCALENDAR\r\n"
+		)
+	}
+}
@@ -0,0 +1,414 @@
+use std::{fmt::Write, fs, io, path::Path, str::FromStr};
+
+use chrono::{DateTime, Days, Local, NaiveDate};
+
+use crate::config::WorkingGroupInfo;
+use crate::filters::Filters;
+use crate::issues_actions::query_actions;
+use crate::returned_issue::ReturnedDatedIssue;
+use crate::stats::fetch_dated;
+
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+const MAX_WEEKS_OVERDUE: usize = 12;
+const SVG_WIDTH: usize = 640;
+const SVG_HEIGHT: usize = 320;
+const MARGIN: usize = 40;
+
+/// Which chart to draw
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Chart {
+	/// Open actions, by how many weeks overdue they are
+	Aging,
+	/// Open comment requests at the end of each week
+	Comments,
+}
+
+impl FromStr for Chart {
+	type Err = String;
+
+	fn from_str(name: &str) -> Result<Self, Self::Err> {
+		match name {
+			"aging" => Ok(Chart::Aging),
+			"comments" => Ok(Chart::Comments),
+			_ => Err(String::from("expected one of: aging, comments")),
+		}
+	}
+}
+
+/// The figures to plot, with a label for each
+struct Series {
+	title: String,
+	labels: Vec<String>,
+	values: Vec<usize>,
+	/// Histograms are drawn as bars; values over time as a line
+	over_time: bool,
+}
+
+/// Draw a chart, as a sparkline in the terminal, or as a standalone SVG file
+///
+/// The aging chart covers the WG's and all TFs' open actions. The comments chart covers the last
+/// `weeks` weeks, and up to `limit` each of the open comment requests, and those closed during that
+/// time.
+pub fn chart(
+	group_name: &str,
+	repos: &WorkingGroupInfo,
+	chart: Chart,
+	weeks: u32,
+	limit: u32,
+	svg: &Option<&Path>,
+	verbose: &bool,
+) -> io::Result<()> {
+	let today = Local::now().date_naive();
+
+	let series = match chart {
+		Chart::Aging => {
+			let dues: Vec<Option<NaiveDate>> = query_actions(
+				&[(group_name, repos)],
+				&None,
+				&false,
+				&false,
+				&true,
+				&Some(vec![]),
+				&Filters::default(),
+				verbose,
+			)
+			.iter()
+			.map(|dated| dated.due)
+			.collect();
+			aging(group_name, &dues, today)
+		}
+		Chart::Comments => {
+			let Some(horizontal_review) = &repos.horizontal_review else {
+				println!("Group '{group_name}' is not a horizontal review group.");
+				return Ok(());
+			};
+			// Requests closed before the first week's end weren't open during the chart.
+			let closed_since = Filters {
+				closed_since: week_ends(today, weeks).first().copied(),
+				..Default::default()
+			};
+			let mut requests = fetch_dated(
+				&horizontal_review.comments,
+				Some("open"),
+				&Filters::default(),
+				limit,
+				verbose,
+			);
+			requests.extend(fetch_dated(
+				&horizontal_review.comments,
+				Some("closed"),
+				&closed_since,
+				limit,
+				verbose,
+			));
+			open_over_time(group_name, &requests, today, weeks)
+		}
+	};
+
+	match svg {
+		Some(path) => {
+			fs::write(path, render_svg(&series))?;
+			println!("Saved chart as: {path:?}")
+		}
+		None => print!("{}", render_text(&series)),
+	}
+
+	Ok(())
+}
+
+fn aging(group_name: &str, dues: &[Option<NaiveDate>], today: NaiveDate) -> Series {
+	let mut values = vec![0; MAX_WEEKS_OVERDUE + 1];
+	for due in dues.iter().flatten().filter(|due| **due < today) {
+		let weeks = ((today - *due).num_days() / 7) as usize;
+		values[weeks.min(MAX_WEEKS_OVERDUE)] += 1
+	}
+
+	let mut labels: Vec<String> = (0..MAX_WEEKS_OVERDUE)
+		.map(|week| week.to_string())
+		.collect();
+	labels.push(format!("{MAX_WEEKS_OVERDUE}+"));
+
+	Series {
+		title: format!("Overdue '{group_name}' actions, by weeks overdue"),
+		labels,
+		values,
+		over_time: false,
+	}
+}
+
+fn open_over_time(
+	group_name: &str,
+	requests: &[ReturnedDatedIssue],
+	today: NaiveDate,
+	weeks: u32,
+) -> Series {
+	let date = |timestamp: &str| {
+		DateTime::parse_from_rfc3339(timestamp)
+			.ok()
+			.map(|date_time| date_time.date_naive())
+	};
+	let spans: Vec<(NaiveDate, Option<NaiveDate>)> = requests
+		.iter()
+		.filter_map(|request| {
+			let created = date(&request.created_at)?;
+			let closed = request
+				.closed_at
+				.as_deref()
+				.and_then(date)
+				.filter(|closed| *closed >= created);
+			Some((created, closed))
+		})
+		.collect();
+
+	let week_ends = week_ends(today, weeks);
+	let values = week_ends
+		.iter()
+		.map(|end| {
+			spans
+				.iter()
+				.filter(|(created, closed)| {
+					created <= end && closed.is_none_or(|closed| closed > *end)
+				})
+				.count()
+		})
+		.collect();
+
+	Series {
+		title: format!("Open '{group_name}' comment requests, by week"),
+		labels: week_ends.iter().map(|end| end.to_string()).collect(),
+		values,
+		over_time: true,
+	}
+}
+
+/// The last day of each of the last `weeks` weeks, ending today, oldest first
+fn week_ends(today: NaiveDate, weeks: u32) -> Vec<NaiveDate> {
+	(0..weeks)
+		.rev()
+		.map(|ago| today - Days::new(7 * ago as u64))
+		.collect()
+}
+
+fn sparkline(values: &[usize]) -> String {
+	let max = values.iter().copied().max().unwrap_or(0);
+	values
+		.iter()
+		.map(|value| SPARKS[(value * (SPARKS.len() - 1)).checked_div(max).unwrap_or(0)])
+		.collect()
+}
+
+fn render_text(series: &Series) -> String {
+	let mut out = format!("{}\n\n{}\n", series.title, sparkline(&series.values));
+	if let (Some(first), Some(last)) = (series.labels.first(), series.labels.last()) {
+		writeln!(out, "{first} to {last}").unwrap();
+	}
+	let max = series.values.iter().copied().max().unwrap_or(0);
+	let total: usize = series.values.iter().sum();
+	if series.over_time {
+		let latest = series.values.last().copied().unwrap_or(0);
+		writeln!(out, "Latest: {latest}; highest: {max}").unwrap();
+	} else {
+		writeln!(out, "Total: {total}; highest: {max}").unwrap();
+	}
+	out
+}
+
+/// Make a standalone SVG image, with a text alternative giving all of the figures
+fn render_svg(series: &Series) -> String {
+	let plot_width = (SVG_WIDTH - 2 * MARGIN) as f64;
+	let plot_height = (SVG_HEIGHT - 2 * MARGIN) as f64;
+	let max = series.values.iter().copied().max().unwrap_or(0).max(1) as f64;
+	let count = series.values.len().max(1) as f64;
+	let step = plot_width / count;
+	let bottom = (SVG_HEIGHT - MARGIN) as f64;
+	let y = |value: usize| bottom - value as f64 * plot_height / max;
+	let escape = |text: &str| {
+		text.replace('&', "&amp;")
+			.replace('<', "&lt;")
+			.replace('>', "&gt;")
+			.replace('\'', "&apos;")
+			.replace('"', "&quot;")
+	};
+
+	let figures: Vec<String> = series
+		.labels
+		.iter()
+		.zip(&series.values)
+		.map(|(label, value)| format!("{label}: {value}"))
+		.collect();
+
+	let mut out = String::new();
+	writeln!(
+		out,
+		r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {SVG_WIDTH} {SVG_HEIGHT}" role="img" aria-labelledby="title desc">"#
+	)
+	.unwrap();
+	writeln!(
+		out,
+		r#"<title id="title">{}</title>"#,
+		escape(&series.title)
+	)
+	.unwrap();
+	writeln!(
+		out,
+		r#"<desc id="desc">{}</desc>"#,
+		escape(&figures.join("; "))
+	)
+	.unwrap();
+	writeln!(
+		out,
+		r#"<rect width="{SVG_WIDTH}" height="{SVG_HEIGHT}" fill="white"/>"#
+	)
+	.unwrap();
+	writeln!(
+		out,
+		r#"<text x="{MARGIN}" y="{}" font-family="sans-serif" font-size="16">{}</text>"#,
+		MARGIN / 2,
+		escape(&series.title)
+	)
+	.unwrap();
+	writeln!(
+		out,
+		r#"<line x1="{MARGIN}" y1="{bottom}" x2="{}" y2="{bottom}" stroke="black"/>"#,
+		SVG_WIDTH - MARGIN
+	)
+	.unwrap();
+	writeln!(
+		out,
+		r#"<text x="{}" y="{}" font-family="sans-serif" font-size="12" text-anchor="end">{}</text>"#,
+		MARGIN - 4,
+		MARGIN + 4,
+		max as usize
+	)
+	.unwrap();
+
+	if series.over_time {
+		let points: Vec<String> = series
+			.values
+			.iter()
+			.enumerate()
+			.map(|(index, value)| {
+				format!(
+					"{:.1},{:.1}",
+					MARGIN as f64 + step * (index as f64 + 0.5),
+					y(*value)
+				)
+			})
+			.collect();
+		writeln!(
+			out,
+			r#"<polyline points="{}" fill="none" stroke="black" stroke-width="2"/>"#,
+			points.join(" ")
+		)
+		.unwrap();
+	} else {
+		for (index, value) in series.values.iter().enumerate() {
+			writeln!(
+				out,
+				r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="black"/>"#,
+				MARGIN as f64 + step * index as f64 + step * 0.1,
+				y(*value),
+				step * 0.8,
+				bottom - y(*value)
+			)
+			.unwrap();
+		}
+	}
+
+	// Label the first and last points, to keep the axis uncluttered.
+	for (index, anchor) in [(0, "start"), (series.labels.len().saturating_sub(1), "end")] {
+		if let Some(label) = series.labels.get(index) {
+			let x = if anchor == "start" {
+				MARGIN
+			} else {
+				SVG_WIDTH - MARGIN
+			};
+			writeln!(
+				out,
+				r#"<text x="{x}" y="{}" font-family="sans-serif" font-size="12" text-anchor="{anchor}">{}</text>"#,
+				bottom + 16.0,
+				escape(label)
+			)
+			.unwrap();
+		}
+	}
+
+	out.push_str("</svg>\n");
+	out
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn date(text: &str) -> NaiveDate {
+		NaiveDate::parse_from_str(text, "%Y-%m-%d").unwrap()
+	}
+
+	#[test]
+	fn sparklines() {
+		assert_eq!(sparkline(&[0, 1, 7, 14]), "▁▁▄█");
+		assert_eq!(sparkline(&[0, 0]), "▁▁");
+		assert_eq!(sparkline(&[]), "");
+	}
+
+	#[test]
+	fn aging_buckets() {
+		let today = date("2024-03-31");
+		let dues = [
+			None,
+			Some(date("2024-04-10")),
+			Some(date("2024-03-30")),
+			Some(date("2024-03-20")),
+			Some(date("2023-01-01")),
+		];
+		let series = aging("apa", &dues, today);
+		assert_eq!(series.values[..2], [1, 1]);
+		assert_eq!(series.values[MAX_WEEKS_OVERDUE], 1);
+		assert_eq!(series.values.iter().sum::<usize>(), 3);
+		assert_eq!(series.labels.last().unwrap(), "12+");
+	}
+
+	#[test]
+	fn open_each_week() {
+		let request = |created: &str, closed: Option<&str>| -> ReturnedDatedIssue {
+			serde_json::from_value(serde_json::json!({
+				"closedAt": closed,
+				"createdAt": created,
+				"labels": [],
+				"number": 1,
+				"title": "",
+			}))
+			.unwrap()
+		};
+		let requests = [
+			request("2024-03-01T09:00:00Z", None),
+			// gh gives open issues a placeholder closing date.
+			request("2024-03-20T09:00:00Z", Some("0001-01-01T00:00:00Z")),
+			request("2024-03-01T09:00:00Z", Some("2024-03-25T09:00:00Z")),
+		];
+
+		let series = open_over_time("apa", &requests, date("2024-03-31"), 3);
+		assert_eq!(
+			series.labels,
+			vec!["2024-03-17", "2024-03-24", "2024-03-31"]
+		);
+		assert_eq!(series.values, vec![2, 3, 2]);
+	}
+
+	#[test]
+	fn svg_has_text_alternative() {
+		let series = Series {
+			title: String::from("A & B"),
+			labels: vec![String::from("0"), String::from("1")],
+			values: vec![1, 2],
+			over_time: false,
+		};
+		let svg = render_svg(&series);
+		assert!(svg.starts_with("<svg "));
+		assert!(svg.contains(r#"<title id="title">A &amp; B</title>"#));
+		assert!(svg.contains(r#"<desc id="desc">0: 1; 1: 2</desc>"#));
+		assert_eq!(svg.matches("<rect ").count(), 3);
+		assert!(svg.ends_with("</svg>\n"));
+	}
+}
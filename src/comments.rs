@@ -1,22 +1,22 @@
-use std::io::{self, Write};
 use std::{
-	collections::HashMap,
 	fmt, println,
 	process::Command,
 	str::{self, FromStr},
 };
 
-use regex::Regex;
-
 use crate::config::WorkingGroupInfo;
 use crate::flatten_assignees::flatten_assignees;
-use crate::make_table::make_table;
+use crate::gh;
+use crate::locator::Locator;
 use crate::returned_issue::ReturnedIssueHeavy;
 use crate::showing::showing;
 use crate::status::{LabelStringList, Status, StatusLabel};
+use crate::table_row::{
+	check_columns, render_sorted, sort_rows, SortBy, SortKey, TableOptions, TableRow,
+};
 
 #[derive(Debug, PartialEq)]
-struct SourceLabel {
+pub(crate) struct SourceLabel {
 	group: String,
 }
 
@@ -49,13 +49,14 @@ impl fmt::Display for SourceLabel {
 	}
 }
 
-struct CommentReviewRequest {
-	source_label: Option<SourceLabel>,
-	status: Status,
-	source_issue: String, // TODO: Make it a Locator? Doesn't seem needed.
-	title: String,
-	tracking_assignees: String,
-	tracking_number: u32,
+pub(crate) struct CommentReviewRequest {
+	pub source_label: Option<SourceLabel>,
+	pub status: Status,
+	pub source_issue: String, // TODO: Make it a Locator? Doesn't seem needed.
+	pub title: String,
+	pub tracking_assignees: String,
+	pub tracking_number: u32,
+	pub updated_at: String,
 }
 
 impl CommentReviewRequest {
@@ -82,45 +83,169 @@ impl CommentReviewRequest {
 			title: issue.title,
 			tracking_assignees: flatten_assignees(&issue.assignees),
 			tracking_number: issue.number,
+			updated_at: issue.updated_at,
 		}
 	}
+}
 
-	fn to_vec_string(&self) -> Vec<String> {
-		vec![
-			self.tracking_number.to_string(),
-			self.title.to_string(),
-			if let Some(group) = &self.source_label {
-				group.to_string()
-			} else {
-				String::from("UNKNOWN")
-			},
-			self.status.to_string(),
-			self.tracking_assignees.to_string(),
-			self.source_issue.to_string(),
-		]
+impl TableRow for CommentReviewRequest {
+	const COLUMNS: &'static [&'static str] = &[
+		"id", "title", "spec", "status", "trackers", "issue", "updated",
+	];
+
+	fn cell(&self, column: &str) -> String {
+		match column {
+			"id" => self.tracking_number.to_string(),
+			"title" => self.title.to_string(),
+			"spec" => {
+				if let Some(group) = &self.source_label {
+					group.to_string()
+				} else {
+					String::from("UNKNOWN")
+				}
+			}
+			"status" => self.status.to_string(),
+			"trackers" => self.tracking_assignees.to_string(),
+			"issue" => self.source_issue.to_string(),
+			"updated" => self.updated_at.chars().take(10).collect(),
+			_ => unreachable!("unknown column '{column}'"),
+		}
+	}
+
+	fn sort_key(&self, by: SortBy) -> SortKey {
+		match by {
+			// Comment requests don't have due dates.
+			SortBy::Due => SortKey::date(None),
+			// All requests are in the same repo.
+			SortBy::Repo => SortKey::Text(String::new()),
+			SortBy::Assignee => SortKey::Text(self.tracking_assignees.to_string()),
+			SortBy::Number => SortKey::Number(self.tracking_number),
+			SortBy::Updated => SortKey::Text(self.updated_at.to_string()),
+		}
 	}
 }
 
 // FIXME: DRY with actions, specs?
-/// Query for issue comment requests; output a custom report.
+/// Query for issue comment requests; output a custom report. The listed requests are returned, so
+/// that they can be opened.
 pub fn comments(
 	group_name: &str,
 	repos: &WorkingGroupInfo,
 	status: &LabelStringList,
 	source: &bool,
+	table_options: &TableOptions,
 	verbose: &bool,
-) {
+) -> Vec<Locator> {
 	if repos.horizontal_review.is_none() {
 		println!("Group '{group_name}' is not a horizontal review group.");
-		return;
+		return vec![];
+	}
+
+	if let Err(message) = check_columns::<CommentReviewRequest>(table_options) {
+		println!("{message}");
+		return vec![];
 	}
 
+	let comments_repo = &repos.horizontal_review.as_ref().unwrap().comments;
+	let reviews = fetch_comment_requests(comments_repo, status, &None, None, verbose);
+
+	// DRY with specs
+	if reviews.is_empty() {
+		// TODO: Make this neater a la .join() for the vec
+		println!("No comment review requests found");
+		return vec![];
+	} else {
+		println!(
+			"{} open review requests in {}\n",
+			showing(reviews.len()),
+			comments_repo
+		)
+	}
+
+	let mut columns = vec!["id", "title", "spec", "status", "trackers"];
+	if *source {
+		columns.push("issue")
+	}
+
+	let mut requests: Vec<CommentReviewRequest> = reviews
+		.into_iter()
+		.map(CommentReviewRequest::from)
+		.collect();
+	sort_rows(&mut requests, table_options);
+	let listed = requests
+		.iter()
+		.map(|request| {
+			Locator::from_name_with_owner(comments_repo, request.tracking_number)
+				.expect("should be able to make a Locator from returned issue")
+		})
+		.collect();
+
+	// FIXME: don't do either of these limitations if we don't need to.
+	match render_sorted(
+		&requests,
+		&columns,
+		table_options,
+		&[("spec", 15), ("trackers", 15)],
+	) {
+		Ok(table) => println!("{table}"),
+		Err(message) => println!("{message}"),
+	}
+
+	listed
+}
+
+/// Query for open issue comment requests in the given repo (up to `limit` of them, if given,
+/// rather than the first 30).
+pub(crate) fn query_comment_requests(
+	comments_repo: &str,
+	status: &LabelStringList,
+	assignee: &Option<String>,
+	limit: Option<u32>,
+	verbose: &bool,
+) -> Vec<CommentReviewRequest> {
+	fetch_comment_requests(comments_repo, status, assignee, limit, verbose)
+		.into_iter()
+		.map(CommentReviewRequest::from)
+		.collect()
+}
+
+/// As [`query_comment_requests()`], but if `gh` fails, the error is returned, rather than stopping
+pub(crate) fn try_query_comment_requests(
+	comments_repo: &str,
+	status: &LabelStringList,
+	assignee: &Option<String>,
+	limit: Option<u32>,
+	verbose: &bool,
+) -> Result<Vec<CommentReviewRequest>, String> {
+	Ok(
+		try_fetch_comment_requests(comments_repo, status, assignee, limit, verbose)?
+			.into_iter()
+			.map(CommentReviewRequest::from)
+			.collect(),
+	)
+}
+
+fn fetch_comment_requests(
+	comments_repo: &str,
+	status: &LabelStringList,
+	assignee: &Option<String>,
+	limit: Option<u32>,
+	verbose: &bool,
+) -> Vec<ReturnedIssueHeavy> {
+	try_fetch_comment_requests(comments_repo, status, assignee, limit, verbose)
+		.unwrap_or_else(gh::fail)
+}
+
+fn try_fetch_comment_requests(
+	comments_repo: &str,
+	status: &LabelStringList,
+	assignee: &Option<String>,
+	limit: Option<u32>,
+	verbose: &bool,
+) -> Result<Vec<ReturnedIssueHeavy>, String> {
 	let mut cmd = Command::new("gh");
 	cmd.args(["search", "issues"])
-		.args([
-			"--repo",
-			&repos.horizontal_review.as_ref().unwrap().comments,
-		])
+		.args(["--repo", comments_repo])
 		.args(["--state", "open"])
 		.args([
 			"--json",
@@ -132,78 +257,33 @@ pub fn comments(
 		cmd.args(["--label", &label.to_string()]); // TODO: neaten / idiomatic
 	}
 
-	if *verbose {
-		println!("Comment review: running: {cmd:?}");
+	if let Some(user) = assignee {
+		cmd.args(["--assignee", user]);
 	}
-	let output = cmd.output().expect("'gh' should run");
-
-	if output.status.success() {
-		let out = str::from_utf8(&output.stdout).expect("got non-utf8 data from 'gh'");
-		let reviews: Vec<ReturnedIssueHeavy> = serde_json::from_str(out).unwrap();
-
-		// DRY with specs
-		if reviews.is_empty() {
-			// TODO: Make this neater a la .join() for the vec
-			println!("No comment review requests found");
-			return;
-		} else {
-			println!(
-				"{} open review requests in {}\n",
-				showing(reviews.len()),
-				repos.horizontal_review.as_ref().unwrap().comments // FIXME: DRY with above (here and in specs)
-			)
-		}
 
-		// TODO: more functional?
-		let mut rows: Vec<Vec<String>> = vec![];
-		for request in reviews {
-			if *source {
-				rows.push(CommentReviewRequest::from(request).to_vec_string());
-			} else {
-				let with_source = CommentReviewRequest::from(request).to_vec_string();
-				let without_source = &with_source[0..with_source.len() - 1];
-				rows.push(without_source.to_vec())
-			}
-		}
+	if let Some(limit) = limit {
+		cmd.args(["--limit", &limit.to_string()]);
+	}
 
-		let mut max_widths = HashMap::new();
-		// FIXME: don't do either of these limitations if we don't need to.
-		max_widths.insert(2, 15); // SPEC
-		max_widths.insert(4, 15); // TRACKERS
-
-		let table = if *source {
-			make_table(
-				vec!["ID", "TITLE", "SPEC", "STATUS", "TRACKERS", "ISSUE"],
-				rows,
-				Some(max_widths),
-			)
-		} else {
-			make_table(
-				vec!["ID", "TITLE", "SPEC", "STATUS", "TRACKERS"],
-				rows,
-				Some(max_widths),
-			)
-		};
-		println!("{table}")
-	} else {
-		io::stdout().write_all(&output.stdout).unwrap();
-		io::stderr().write_all(&output.stderr).unwrap();
-		panic!("'gh' did not run successfully")
+	if *verbose {
+		println!("Comment review: running: {cmd:?}");
+	}
+	let requests: Vec<ReturnedIssueHeavy> = gh::run_json(&mut cmd)?;
+	if let Some(limit) = limit {
+		gh::note_if_limited("Comment review", requests.len(), limit);
 	}
+	Ok(requests)
 }
 
 // TODO: change to return result, because not having the link is an error?
 fn get_source_issue_locator(body: &str) -> String {
-	let re = Regex::new(r"§ https://github.com/(.+)/(.+)/.+/(\d+)").unwrap();
-
-	if let Some(caps) = re.captures(body) {
-		let owner = caps.get(1).unwrap().as_str();
-		let repo = caps.get(2).unwrap().as_str();
-		let number = caps.get(3).unwrap().as_str();
-		return format!("{}/{}#{}", owner, repo, number);
-	}
-
-	String::from("UNKNOWN!")
+	body.split("§ ")
+		.nth(1)
+		.and_then(|rest| rest.split_whitespace().next())
+		// The link may be followed by punctuation, such as a closing bracket or full stop.
+		.map(|url| url.trim_end_matches(|c: char| c.is_ascii_punctuation() && c != '/'))
+		.and_then(|url| Locator::from_str(url).ok())
+		.map_or_else(|| String::from("UNKNOWN!"), |locator| locator.to_string())
 }
 
 #[cfg(test)]
@@ -241,6 +321,16 @@ mod tests_get_locator {
 			String::from("whatwg/html#8352")
 		);
 	}
+
+	#[test]
+	fn trailing_punctuation() {
+		assert_eq!(
+			get_source_issue_locator(
+				"(See the discussion at: § https://github.com/w3c/csswg-drafts/issues/42)."
+			),
+			String::from("w3c/csswg-drafts#42")
+		);
+	}
 }
 
 #[cfg(test)]
@@ -5,24 +5,47 @@ use std::{
 	path::PathBuf,
 };
 
+#[cfg(target_os = "macos")]
 use etcetera::base_strategy::{self, BaseStrategy};
 use regex::Regex;
 
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
+mod discover;
+mod layers;
+mod merge;
+mod migrate;
 mod repos;
 mod settings;
+mod validate;
 
-pub use repos::{Repos, WgOrTfRepos, WorkingGroupInfo};
+pub use discover::discover;
+pub use layers::{
+	env_wg, Effective, ProjectConfig, Source, CONFIG_DIR_VAR, NONINTERACTIVE_VAR,
+	PROJECT_FILE_NAME, WG_VAR,
+};
+pub use merge::{Conflict, MergeReport};
+pub use repos::{repo_exists, Repos, WgOrTfRepos, WorkingGroupInfo};
 pub use settings::Settings;
+pub use validate::{Problem, Severity};
 
 const APP_DIR: &str = "nu-tracker";
 
 pub enum ConfigError {
 	DirNope,
 	IoError(io::ErrorKind),
-	JsonError { file_name: String, details: String },
+	JsonError {
+		file_name: String,
+		details: String,
+	},
 	JsonMissingVersion(String),
+	Invalid {
+		file_name: String,
+		problems: Vec<Problem>,
+	},
+	Edit(String),
+	Discover(String),
+	NonInteractive(String),
 }
 
 impl error::Error for ConfigError {}
@@ -45,6 +68,20 @@ impl fmt::Display for ConfigError {
 			ConfigError::JsonMissingVersion(file_name) => {
 				write!(f, "Can't find version number in {file_name}")?
 			}
+			ConfigError::Edit(message) => write!(f, "{message}")?,
+			ConfigError::Discover(message) => write!(f, "{message}")?,
+			ConfigError::NonInteractive(message) => {
+				write!(f, "{message} (running non-interactively)")?
+			}
+			ConfigError::Invalid {
+				file_name,
+				problems,
+			} => {
+				write!(f, "{file_name} has problems:")?;
+				for problem in problems {
+					write!(f, "\n{problem}")?
+				}
+			}
 		}
 		Ok(())
 	}
@@ -56,16 +93,46 @@ impl From<io::Error> for ConfigError {
 	}
 }
 
-enum InitialContent {
+enum InitialContent<'a> {
 	Static(String),
-	Creator(fn() -> String),
+	Creator(Box<dyn FnOnce() -> Result<String, ConfigError> + 'a>),
 }
 
-#[derive(Serialize, Deserialize)]
+/// Whether the user may be asked questions on first run
+#[derive(Clone, Copy)]
+pub enum Prompting<'a> {
+	Interactive,
+	/// Use the platform-default config directory, and the given WG (if any) as the default WG
+	NonInteractive(Option<&'a str>),
+}
+
+impl<'a> Prompting<'a> {
+	/// Work out whether to prompt, from the command line switch and the NT_NONINTERACTIVE
+	/// environment variable, along with the WG given on the command line (if any)
+	pub fn new(yes: bool, working_group: Option<&'a str>) -> Self {
+		if yes || layers::env_non_interactive() {
+			Prompting::NonInteractive(working_group)
+		} else {
+			Prompting::Interactive
+		}
+	}
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 struct Meta {
 	version: u16,
 }
 
+/// Return where the config directory comes from
+pub fn config_dir_source() -> Source {
+	if layers::env_config_dir().is_some() {
+		Source::Environment(CONFIG_DIR_VAR)
+	} else {
+		Source::PlatformDefault
+	}
+}
+
 /// Return the in-use config directory
 pub fn config_dir() -> PathBuf {
 	for dir in default_dirs() {
@@ -82,8 +149,8 @@ pub fn config_dir() -> PathBuf {
 /// Ensure that the config file directory exists
 ///
 /// If no config file directory exists, this will create one, after asking the user for
-/// confirmation.
-pub fn ensure_dir() -> Result<(), ConfigError> {
+/// confirmation (unless running non-interactively, in which case the platform default is used).
+pub fn ensure_dir(prompting: Prompting) -> Result<(), ConfigError> {
 	let candidates = default_dirs();
 
 	for dir in &candidates {
@@ -92,6 +159,12 @@ pub fn ensure_dir() -> Result<(), ConfigError> {
 		}
 	}
 
+	if let Prompting::NonInteractive(_) = prompting {
+		std::fs::create_dir_all(&candidates[0])?;
+		println!("Created config directory: {:?}", &candidates[0]);
+		return Ok(());
+	}
+
 	println!("Nu Tracker needs to create a config file directory.\n");
 
 	if candidates.len() == 1 {
@@ -109,13 +182,13 @@ pub fn ensure_dir() -> Result<(), ConfigError> {
 	}
 
 	let targ: Option<&PathBuf> = if candidates.len() == 1 {
-		if get_input(" [y/*]") == "y" {
+		if get_input(" [y/*]")? == "y" {
 			Some(&candidates[0])
 		} else {
 			None
 		}
 	} else {
-		get_path_index(&candidates)
+		get_path_index(&candidates)?
 	};
 
 	if let Some(dir) = targ {
@@ -132,8 +205,15 @@ pub fn ensure_dir() -> Result<(), ConfigError> {
 ///
 /// On other platforms this just returns a one-path Vec: the platform-default one.
 ///
+/// If the NT_CONFIG_DIR environment variable is set, this returns only that path.
+///
 /// After creation, [`config_dir()`] can be called to find the in-use path.
 fn default_dirs() -> Vec<PathBuf> {
+	if let Some(dir) = layers::env_config_dir() {
+		return vec![dir];
+	}
+
+	#[allow(unused_mut)]
 	let mut candidates = vec![dirs::config_dir()
 		.expect("should be able to figure out platform config path")
 		.join(APP_DIR)];
@@ -151,27 +231,34 @@ fn default_dirs() -> Vec<PathBuf> {
 	candidates
 }
 
-fn get_input(prompt: &str) -> String {
+/// Ask the user for input; fails if there is none (e.g. stdin is not a terminal)
+fn get_input(prompt: &str) -> Result<String, ConfigError> {
 	let mut input = String::new();
 
 	print!("{prompt} ");
 	let _ = std::io::stdout().flush();
 
-	std::io::stdin()
+	let read = std::io::stdin()
 		.read_line(&mut input)
 		.expect("should be able to read user's input");
 
-	input.trim().to_string()
+	if read == 0 {
+		return Err(ConfigError::NonInteractive(format!(
+			"No input available: to run without questions, use --yes or set {NONINTERACTIVE_VAR}"
+		)));
+	}
+
+	Ok(input.trim().to_string())
 }
 
-fn get_path_index(candidates: &[PathBuf]) -> Option<&PathBuf> {
+fn get_path_index(candidates: &[PathBuf]) -> Result<Option<&PathBuf>, ConfigError> {
 	loop {
-		let input = get_input("\nCreate path:");
+		let input = get_input("\nCreate path:")?;
 		if !input.is_empty() {
 			if let Ok(input) = input.trim().parse::<usize>() {
 				let chosen = &candidates.get(input);
 				if chosen.is_some() {
-					break *chosen;
+					break Ok(*chosen);
 				} else {
 					print!("Invalid path number.")
 				}
@@ -179,7 +266,7 @@ fn get_path_index(candidates: &[PathBuf]) -> Option<&PathBuf> {
 				print!("Please enter a path number, or press ENTER/RETURN to exit.")
 			}
 		} else {
-			return None;
+			return Ok(None);
 		}
 	}
 }
@@ -191,6 +278,7 @@ fn get_or_create<T>(
 	current_version: &str,
 	mismatch_severity: &str,
 	mismatch_message: Option<String>,
+	validator: Option<fn(&T, &str) -> Vec<Problem>>,
 ) -> Result<T, ConfigError>
 where
 	T: DeserializeOwned,
@@ -200,7 +288,7 @@ where
 	if !file_path.exists() {
 		let content = match init {
 			InitialContent::Static(content) => content,
-			InitialContent::Creator(callable) => callable(),
+			InitialContent::Creator(callable) => callable()?,
 		};
 
 		std::fs::write(&file_path, content)?;
@@ -216,13 +304,34 @@ where
 		mismatch_message,
 	)?;
 
-	match serde_json::from_str::<T>(&json_string) {
-		Ok(thing) => Ok(thing),
-		Err(error) => Err(ConfigError::JsonError {
-			file_name: file_name.to_string(),
-			details: error.to_string(),
-		}),
+	let thing = match serde_json::from_str::<T>(&json_string) {
+		Ok(thing) => thing,
+		Err(error) => {
+			return Err(ConfigError::JsonError {
+				file_name: file_name.to_string(),
+				details: error.to_string(),
+			})
+		}
+	};
+
+	if let Some(validate) = validator {
+		let (errors, warnings): (Vec<Problem>, Vec<Problem>) = validate(&thing, &json_string)
+			.into_iter()
+			.partition(|problem| problem.severity == Severity::Error);
+
+		for warning in warnings {
+			println!("{file_name}: {warning}")
+		}
+
+		if !errors.is_empty() {
+			return Err(ConfigError::Invalid {
+				file_name: file_name.to_string(),
+				problems: errors,
+			});
+		}
 	}
+
+	Ok(thing)
 }
 
 // NOTE: Current version is str because we get it from the file directly.
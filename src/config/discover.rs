@@ -0,0 +1,212 @@
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use super::repos::{WgOrTfRepos, WorkingGroupInfo};
+use super::ConfigError;
+
+/// A repo that belongs to the WG, per its `w3c.json` data
+#[derive(Debug, PartialEq)]
+struct GroupRepo {
+	full_name: String,
+	name: String,
+}
+
+/// Propose repo info for a WG, and its TFs, from W3C group/repository data
+///
+/// The data may be either a list of repos, or an object with a "repos" list, and optionally a
+/// "groups" object mapping W3C group IDs to group info (as in the report produced by
+/// [validate-repos](https://github.com/w3c/validate-repos)). Each repo needs its full name
+/// ("fullName", "nameWithOwner", or "owner.login" and "name"), and the contents of its `w3c.json`
+/// file ("w3cjson" or "w3c"). The groups in `w3c.json` may be given as "wg/<shortname>" or as W3C
+/// group IDs (which are looked up in "groups").
+///
+/// Archived repos are skipped. The repo with the same name as the WG becomes the main repo (or,
+/// failing that, the first repo alphabetically). Repos named after a TF (ending in "tf" or "-tf")
+/// become the main repos of TFs; all others become the WG's "other" repos.
+pub fn discover(wg: &str, json: &str) -> Result<WorkingGroupInfo, ConfigError> {
+	let data: Value = serde_json::from_str(json).map_err(|error| ConfigError::JsonError {
+		file_name: String::from("group data"),
+		details: error.to_string(),
+	})?;
+
+	let (repos, groups) = match &data {
+		Value::Array(repos) => (repos.as_slice(), None),
+		Value::Object(object) => (
+			object
+				.get("repos")
+				.and_then(Value::as_array)
+				.map(Vec::as_slice)
+				.unwrap_or_default(),
+			object.get("groups"),
+		),
+		_ => (&[] as &[Value], None),
+	};
+
+	let mut found: Vec<GroupRepo> = repos
+		.iter()
+		.filter(|repo| !is_archived(repo))
+		.filter(|repo| belongs_to(repo, wg, groups))
+		.filter_map(full_name)
+		.collect();
+	found.sort_by(|a, b| a.full_name.cmp(&b.full_name));
+	found.dedup();
+
+	if found.is_empty() {
+		return Err(ConfigError::Discover(format!(
+			"No (unarchived) repos found for WG '{wg}'"
+		)));
+	}
+
+	let main_index = found.iter().position(|repo| repo.name == wg).unwrap_or(0);
+	let main = found.remove(main_index);
+
+	let mut others: Vec<String> = vec![];
+	let mut task_forces: BTreeMap<String, WgOrTfRepos> = BTreeMap::new();
+	for repo in found {
+		match task_force_name(&repo.name) {
+			Some(tf) => {
+				task_forces.insert(
+					tf,
+					WgOrTfRepos {
+						main: repo.full_name,
+						others: None,
+					},
+				);
+			}
+			None => others.push(repo.full_name),
+		}
+	}
+
+	Ok(WorkingGroupInfo {
+		horizontal_review: None,
+		working_group: WgOrTfRepos {
+			main: main.full_name,
+			others: if others.is_empty() {
+				None
+			} else {
+				Some(others)
+			},
+		},
+		task_forces,
+	})
+}
+
+fn is_archived(repo: &Value) -> bool {
+	["isArchived", "archived"]
+		.iter()
+		.any(|key| repo.get(key).and_then(Value::as_bool).unwrap_or(false))
+}
+
+fn full_name(repo: &Value) -> Option<GroupRepo> {
+	let full_name = ["fullName", "full_name", "nameWithOwner"]
+		.iter()
+		.find_map(|key| repo.get(key).and_then(Value::as_str))
+		.map(String::from)
+		.or_else(|| {
+			let owner = repo.get("owner")?.get("login")?.as_str()?;
+			let name = repo.get("name")?.as_str()?;
+			Some(format!("{owner}/{name}"))
+		})?;
+
+	let name = full_name.split_once('/')?.1.to_string();
+	Some(GroupRepo { full_name, name })
+}
+
+fn belongs_to(repo: &Value, wg: &str, groups: Option<&Value>) -> bool {
+	let w3c_json = match ["w3cjson", "w3c"].iter().find_map(|key| repo.get(key)) {
+		Some(w3c_json) => w3c_json,
+		None => return false,
+	};
+
+	let group_ids: Vec<&Value> = match w3c_json.get("group") {
+		Some(Value::Array(ids)) => ids.iter().collect(),
+		Some(id) => vec![id],
+		None => vec![],
+	};
+
+	group_ids.iter().any(|id| match id {
+		Value::String(id) if id.contains('/') => id == &format!("wg/{wg}"),
+		Value::String(id) => group_shortname(groups, id) == Some(wg),
+		Value::Number(id) => group_shortname(groups, &id.to_string()) == Some(wg),
+		_ => false,
+	})
+}
+
+fn group_shortname<'a>(groups: Option<&'a Value>, id: &str) -> Option<&'a str> {
+	let group = groups?.get(id)?;
+	["shortname", "shortName"]
+		.iter()
+		.find_map(|key| group.get(key).and_then(Value::as_str))
+}
+
+/// If the repo name looks like a TF's (e.g. "rqtf" or "rq-tf"), return the TF name
+fn task_force_name(repo_name: &str) -> Option<String> {
+	let name = repo_name.strip_suffix("tf")?;
+	let name = name.strip_suffix('-').unwrap_or(name);
+	if name.is_empty() {
+		None
+	} else {
+		Some(name.to_string())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const REPORT: &str = r#"{
+		"groups": {
+			"83907": { "name": "Accessible Platform Architectures Working Group", "shortname": "apa" },
+			"35422": { "name": "Accessibility Guidelines Working Group", "shortname": "ag" }
+		},
+		"repos": [
+			{ "owner": { "login": "w3c" }, "name": "rqtf", "w3cjson": { "group": [83907] } },
+			{ "owner": { "login": "w3c" }, "name": "apa", "w3cjson": { "group": [83907] } },
+			{ "owner": { "login": "w3c" }, "name": "wcag", "w3cjson": { "group": [35422] } },
+			{ "owner": { "login": "w3c" }, "name": "coga", "w3cjson": { "group": [35422, 83907] } },
+			{ "owner": { "login": "w3c" }, "name": "old", "isArchived": true, "w3cjson": { "group": [83907] } },
+			{ "owner": { "login": "w3c" }, "name": "no-w3c-json" }
+		]
+	}"#;
+
+	#[test]
+	fn from_validate_repos_report() {
+		let info = discover("apa", REPORT).unwrap();
+		assert_eq!(info.working_group.main, "w3c/apa");
+		assert_eq!(
+			info.working_group.others,
+			Some(vec![String::from("w3c/coga")])
+		);
+		assert_eq!(info.task_forces.len(), 1);
+		assert_eq!(info.task_forces["rq"].main, "w3c/rqtf");
+	}
+
+	#[test]
+	fn from_repo_list_with_group_paths() {
+		let list = r#"[
+			{ "fullName": "w3c/wcag2ict", "w3c": { "group": "wg/ag" } },
+			{ "fullName": "w3c/wcag", "w3c": { "group": ["wg/ag"] } }
+		]"#;
+		let info = discover("ag", list).unwrap();
+		assert_eq!(info.working_group.main, "w3c/wcag");
+		assert_eq!(
+			info.working_group.others,
+			Some(vec![String::from("w3c/wcag2ict")])
+		);
+		assert!(info.task_forces.is_empty());
+	}
+
+	#[test]
+	fn unknown_group() {
+		assert!(discover("nope", REPORT).is_err())
+	}
+
+	#[test]
+	fn task_force_names() {
+		assert_eq!(task_force_name("rqtf"), Some(String::from("rq")));
+		assert_eq!(task_force_name("coga-tf"), Some(String::from("coga")));
+		assert_eq!(task_force_name("tf"), None);
+		assert_eq!(task_force_name("apa"), None);
+	}
+}
@@ -0,0 +1,332 @@
+use std::{
+	collections::BTreeMap,
+	env, fmt, fs,
+	path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+use super::repos::{HorizontalReview, Repos, WgOrTfRepos, WorkingGroupInfo};
+use super::validate::{validate_groups, GroupRepos, Problem, Severity};
+use super::ConfigError;
+
+/// Name of the project-local config file, which may be checked in to a group's repo
+pub const PROJECT_FILE_NAME: &str = ".nu-tracker.json";
+/// Environment variable that overrides the config directory
+pub const CONFIG_DIR_VAR: &str = "NT_CONFIG_DIR";
+/// Environment variable that overrides the default WG
+pub const WG_VAR: &str = "NT_WG";
+/// Environment variable that stops first-run questions being asked (as with --yes)
+pub const NONINTERACTIVE_VAR: &str = "NT_NONINTERACTIVE";
+
+/// Where an effective config value came from
+#[derive(Clone, Debug, PartialEq)]
+pub enum Source {
+	CommandLine,
+	Environment(&'static str),
+	ProjectFile(PathBuf),
+	SettingsFile,
+	ReposFile,
+	PlatformDefault,
+}
+
+impl fmt::Display for Source {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Source::CommandLine => write!(f, "command line"),
+			Source::Environment(var) => write!(f, "{var} environment variable"),
+			Source::ProjectFile(path) => write!(f, "project file {path:?}"),
+			Source::SettingsFile => write!(f, "settings file"),
+			Source::ReposFile => write!(f, "repos file"),
+			Source::PlatformDefault => write!(f, "platform default"),
+		}
+	}
+}
+
+/// Project-local config, which overrides or extends the user's repos and settings
+///
+/// WGs that aren't in the repos file are added (and must have a "workingGroup"). For WGs that
+/// are, any given horizontal review or WG repos replace the existing ones, and TFs are added or
+/// replaced.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ProjectConfig {
+	#[serde(skip)]
+	path: PathBuf,
+	working_group: Option<String>,
+	#[serde(default)]
+	repos: BTreeMap<String, ProjectGroupInfo>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct ProjectGroupInfo {
+	horizontal_review: Option<HorizontalReview>,
+	working_group: Option<WgOrTfRepos>,
+	#[serde(default)]
+	task_forces: BTreeMap<String, WgOrTfRepos>,
+}
+
+impl ProjectConfig {
+	/// Look for a project file in the current directory, and its ancestors
+	pub fn find() -> Result<Option<Self>, ConfigError> {
+		match env::current_dir() {
+			Ok(dir) => Self::find_from(&dir),
+			Err(_) => Ok(None),
+		}
+	}
+
+	fn find_from(start: &Path) -> Result<Option<Self>, ConfigError> {
+		for dir in start.ancestors() {
+			let path = dir.join(PROJECT_FILE_NAME);
+			if path.is_file() {
+				return Self::parse(&path, &fs::read_to_string(&path)?).map(Some);
+			}
+		}
+		Ok(None)
+	}
+
+	/// Parse a project file, and check its repos as the repos file's are checked on loading
+	fn parse(path: &Path, json: &str) -> Result<Self, ConfigError> {
+		let file_name = path.to_string_lossy().to_string();
+		let mut project: ProjectConfig =
+			serde_json::from_str(json).map_err(|error| ConfigError::JsonError {
+				file_name: file_name.clone(),
+				details: error.to_string(),
+			})?;
+		project.path = path.to_path_buf();
+
+		let (errors, warnings): (Vec<Problem>, Vec<Problem>) = validate_groups(
+			project.repos.iter().map(|(wg_name, info)| {
+				(
+					wg_name,
+					GroupRepos {
+						horizontal_review: info.horizontal_review.as_ref(),
+						working_group: info.working_group.as_ref(),
+						task_forces: &info.task_forces,
+					},
+				)
+			}),
+			json,
+		)
+		.into_iter()
+		.partition(|problem| problem.severity == Severity::Error);
+
+		for warning in warnings {
+			println!("{file_name}: {warning}")
+		}
+		if !errors.is_empty() {
+			return Err(ConfigError::Invalid {
+				file_name,
+				problems: errors,
+			});
+		}
+
+		Ok(project)
+	}
+
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+}
+
+/// The repos and settings that are actually in use, after applying any project file and
+/// environment overrides, along with where each value came from
+pub struct Effective {
+	pub repos: Repos,
+	pub working_group: (String, Source),
+	pub group_sources: BTreeMap<String, Vec<Source>>,
+}
+
+impl Effective {
+	/// Layer the project file (if any) and environment variables over the user's config
+	///
+	/// Precedence (highest first) for the default WG is: the NT_WG environment variable (whose
+	/// value, if set, is given as `env_wg`; see [`env_wg()`]), the project file, then the settings
+	/// file.
+	pub fn new(
+		repos: &Repos,
+		settings_wg: &str,
+		project: Option<&ProjectConfig>,
+		env_wg: Option<String>,
+	) -> Result<Self, ConfigError> {
+		let mut layered = repos.clone();
+		let mut group_sources: BTreeMap<String, Vec<Source>> = repos
+			.wgs_repos()
+			.keys()
+			.map(|wg| (wg.clone(), vec![Source::ReposFile]))
+			.collect();
+
+		let mut working_group = (settings_wg.to_string(), Source::SettingsFile);
+
+		if let Some(project) = project {
+			let source = Source::ProjectFile(project.path.clone());
+
+			for (wg, overrides) in &project.repos {
+				let groups = layered.wgs_repos_mut();
+				match groups.get_mut(wg) {
+					Some(info) => {
+						if let Some(horizontal_review) = &overrides.horizontal_review {
+							info.horizontal_review = Some(horizontal_review.clone())
+						}
+						if let Some(working_group) = &overrides.working_group {
+							info.working_group = working_group.clone()
+						}
+						for (tf, team) in &overrides.task_forces {
+							info.task_forces.insert(tf.clone(), team.clone());
+						}
+					}
+					None => {
+						let working_group = overrides.working_group.clone().ok_or_else(|| {
+							ConfigError::JsonError {
+								file_name: project.path.to_string_lossy().to_string(),
+								details: format!("new WG '{wg}' needs a \"workingGroup\""),
+							}
+						})?;
+						groups.insert(
+							wg.clone(),
+							WorkingGroupInfo {
+								horizontal_review: overrides.horizontal_review.clone(),
+								working_group,
+								task_forces: overrides.task_forces.clone(),
+							},
+						);
+					}
+				}
+				group_sources
+					.entry(wg.clone())
+					.or_default()
+					.push(source.clone());
+			}
+
+			if let Some(wg) = &project.working_group {
+				working_group = (wg.clone(), source);
+			}
+		}
+
+		if let Some(wg) = env_wg {
+			working_group = (wg, Source::Environment(WG_VAR));
+		}
+
+		Ok(Effective {
+			repos: layered,
+			working_group,
+			group_sources,
+		})
+	}
+}
+
+/// Return the default WG given via the environment, if any
+pub fn env_wg() -> Option<String> {
+	env::var(WG_VAR).ok().filter(|wg| !wg.is_empty())
+}
+
+/// Return the config directory given via the environment, if any
+pub(super) fn env_config_dir() -> Option<PathBuf> {
+	env::var_os(CONFIG_DIR_VAR)
+		.filter(|dir| !dir.is_empty())
+		.map(PathBuf::from)
+}
+
+/// Whether NT_NONINTERACTIVE is set to something other than "", "0" or "false"
+pub(super) fn env_non_interactive() -> bool {
+	env::var(NONINTERACTIVE_VAR).is_ok_and(|value| !matches!(value.as_str(), "" | "0" | "false"))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const PROJECT: &str = r#"{
+		"workingGroup": "xyz",
+		"repos": {
+			"apa": { "taskForces": { "new": { "main": "w3c/new-tf" } } },
+			"xyz": { "workingGroup": { "main": "w3c/xyz" } }
+		}
+	}"#;
+
+	#[test]
+	fn project_file_found_in_ancestor() {
+		let root = env::temp_dir().join(format!("nt-layers-{}", std::process::id()));
+		let nested = root.join("a").join("b");
+		fs::create_dir_all(&nested).unwrap();
+		fs::write(root.join(PROJECT_FILE_NAME), PROJECT).unwrap();
+
+		let project = ProjectConfig::find_from(&nested).unwrap().unwrap();
+		assert_eq!(project.path(), root.join(PROJECT_FILE_NAME));
+		assert_eq!(project.working_group, Some(String::from("xyz")));
+
+		fs::remove_dir_all(root).unwrap();
+	}
+
+	#[test]
+	fn project_overrides_and_extends() {
+		let repos = Repos::shipped();
+		let project = ProjectConfig::parse(Path::new(PROJECT_FILE_NAME), PROJECT).unwrap();
+		let effective = Effective::new(&repos, "apa", Some(&project), None).unwrap();
+		let groups = effective.repos.wgs_repos();
+
+		assert!(groups["apa"].task_forces.contains_key("new"));
+		assert!(groups["apa"].task_forces.contains_key("rq"));
+		assert_eq!(groups["xyz"].working_group.main, "w3c/xyz");
+		assert_eq!(effective.working_group.0, "xyz");
+		assert_eq!(
+			effective.group_sources["apa"],
+			vec![
+				Source::ReposFile,
+				Source::ProjectFile(PathBuf::from(PROJECT_FILE_NAME))
+			]
+		);
+		assert!(!repos.wgs_repos().contains_key("xyz"));
+	}
+
+	#[test]
+	fn environment_overrides_project() {
+		let project = ProjectConfig::parse(Path::new(PROJECT_FILE_NAME), PROJECT).unwrap();
+		let effective = Effective::new(
+			&Repos::shipped(),
+			"apa",
+			Some(&project),
+			Some(String::from("ag")),
+		)
+		.unwrap();
+		assert_eq!(
+			effective.working_group,
+			(String::from("ag"), Source::Environment(WG_VAR))
+		);
+	}
+
+	#[test]
+	fn project_repos_validated() {
+		let result = ProjectConfig::parse(
+			Path::new(PROJECT_FILE_NAME),
+			r#"{
+  "repos": {
+    "xyz": { "workingGroup": { "main": "w3c xyz" } }
+  }
+}"#,
+		);
+		match result {
+			Err(ConfigError::Invalid { problems, .. }) => {
+				assert_eq!(problems.len(), 1);
+				assert_eq!((problems[0].line, problems[0].column), (3, 40));
+			}
+			_ => panic!("expected the bad repo name to be reported"),
+		}
+	}
+
+	#[test]
+	fn new_group_needs_working_group() {
+		let project = ProjectConfig::parse(
+			Path::new(PROJECT_FILE_NAME),
+			r#"{ "repos": { "xyz": { "taskForces": {} } } }"#,
+		)
+		.unwrap();
+		assert!(Effective::new(&Repos::shipped(), "apa", Some(&project), None).is_err());
+	}
+
+	#[test]
+	fn unknown_keys_rejected() {
+		assert!(ProjectConfig::parse(Path::new(PROJECT_FILE_NAME), r#"{ "repo": {} }"#).is_err())
+	}
+}
@@ -0,0 +1,417 @@
+use std::{collections::BTreeMap, fmt};
+
+use super::repos::{HorizontalReview, WgOrTfRepos, WorkingGroupInfo};
+
+type Groups = BTreeMap<String, WorkingGroupInfo>;
+
+/// A value that was changed differently both by the user, and upstream
+///
+/// When this happens, the user's value is kept.
+#[derive(Debug, PartialEq)]
+pub struct Conflict {
+	pub path: String,
+	pub yours: String,
+	pub upstream: String,
+}
+
+impl fmt::Display for Conflict {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"CONFLICT: {} (keeping yours)\n- yours:    {}\n+ upstream: {}",
+			self.path, self.yours, self.upstream
+		)
+	}
+}
+
+/// The outcome of merging the user's repos with the upstream ones
+#[derive(Debug, PartialEq)]
+pub struct MergeReport {
+	pub changes: Vec<String>,
+	pub conflicts: Vec<Conflict>,
+}
+
+/// Three-way merge of the user's WG info with upstream WG info
+///
+/// The `base` is the upstream version that the user's file was derived from. If it's not known,
+/// nothing is considered to have been removed on either side, and differing values are reported
+/// as conflicts.
+pub(super) fn merge_groups(
+	base: Option<&Groups>,
+	yours: &Groups,
+	upstream: &Groups,
+) -> (Groups, MergeReport) {
+	let mut merger = Merger {
+		changes: vec![],
+		conflicts: vec![],
+	};
+	let merged = merger.merge_map(
+		"",
+		"WG",
+		base,
+		yours,
+		upstream,
+		|merger, path, base, yours, upstream| merger.merge_group(path, base, yours, upstream),
+	);
+
+	(
+		merged,
+		MergeReport {
+			changes: merger.changes,
+			conflicts: merger.conflicts,
+		},
+	)
+}
+
+struct Merger {
+	changes: Vec<String>,
+	conflicts: Vec<Conflict>,
+}
+
+impl Merger {
+	/// Merge maps of named WGs or TFs, keeping user additions, and picking up upstream ones
+	fn merge_map<T: Clone + PartialEq>(
+		&mut self,
+		parent: &str,
+		kind: &str,
+		base: Option<&BTreeMap<String, T>>,
+		yours: &BTreeMap<String, T>,
+		upstream: &BTreeMap<String, T>,
+		merge_entry: impl Fn(&mut Self, &str, Option<&T>, &T, &T) -> T,
+	) -> BTreeMap<String, T> {
+		let mut merged = BTreeMap::new();
+
+		for (name, your_entry) in yours {
+			let path = format!("{parent}{name}");
+			let base_entry = base.and_then(|b| b.get(name));
+			match upstream.get(name) {
+				Some(upstream_entry) => {
+					let entry = merge_entry(self, &path, base_entry, your_entry, upstream_entry);
+					merged.insert(name.clone(), entry);
+				}
+				None => match base_entry {
+					Some(base_entry) if base_entry == your_entry => self
+						.changes
+						.push(format!("removed {kind} '{path}' (removed upstream)")),
+					Some(_) => {
+						self.conflicts.push(Conflict {
+							path: format!("{kind} '{path}'"),
+							yours: String::from("changed"),
+							upstream: String::from("removed"),
+						});
+						merged.insert(name.clone(), your_entry.clone());
+					}
+					// You added it.
+					None => {
+						merged.insert(name.clone(), your_entry.clone());
+					}
+				},
+			}
+		}
+
+		for (name, upstream_entry) in upstream {
+			if !yours.contains_key(name) {
+				let path = format!("{parent}{name}");
+				if base.is_some_and(|b| b.contains_key(name)) {
+					// You removed it, so leave it out.
+				} else {
+					self.changes.push(format!("added {kind} '{path}'"));
+					merged.insert(name.clone(), upstream_entry.clone());
+				}
+			}
+		}
+
+		merged
+	}
+
+	fn merge_group(
+		&mut self,
+		path: &str,
+		base: Option<&WorkingGroupInfo>,
+		yours: &WorkingGroupInfo,
+		upstream: &WorkingGroupInfo,
+	) -> WorkingGroupInfo {
+		let horizontal_review = self.merge_value(
+			&format!("{path}: horizontal review repos"),
+			base.map(|b| &b.horizontal_review),
+			&yours.horizontal_review,
+			&upstream.horizontal_review,
+			describe_horizontal_review,
+		);
+		let working_group = self.merge_team(
+			&format!("{path} WG"),
+			base.map(|b| &b.working_group),
+			&yours.working_group,
+			&upstream.working_group,
+		);
+		let task_forces = self.merge_map(
+			&format!("{path}/"),
+			"TF",
+			base.map(|b| &b.task_forces),
+			&yours.task_forces,
+			&upstream.task_forces,
+			|merger, path, base, yours, upstream| {
+				merger.merge_team(&format!("{path} TF"), base, yours, upstream)
+			},
+		);
+
+		WorkingGroupInfo {
+			horizontal_review,
+			working_group,
+			task_forces,
+		}
+	}
+
+	fn merge_team(
+		&mut self,
+		path: &str,
+		base: Option<&WgOrTfRepos>,
+		yours: &WgOrTfRepos,
+		upstream: &WgOrTfRepos,
+	) -> WgOrTfRepos {
+		let main = self.merge_value(
+			&format!("{path}: main repo"),
+			base.map(|b| &b.main),
+			&yours.main,
+			&upstream.main,
+			|main| main.to_string(),
+		);
+
+		let empty = vec![];
+		let base_others = base.map(|b| b.others.as_ref().unwrap_or(&empty));
+		let your_others = yours.others.as_ref().unwrap_or(&empty);
+		let upstream_others = upstream.others.as_ref().unwrap_or(&empty);
+
+		let mut others: Vec<String> = vec![];
+		for repo in your_others {
+			let removed_upstream =
+				base_others.is_some_and(|b| b.contains(repo)) && !upstream_others.contains(repo);
+			if removed_upstream {
+				self.changes
+					.push(format!("removed '{repo}' from {path} (removed upstream)"))
+			} else {
+				others.push(repo.clone())
+			}
+		}
+		for repo in upstream_others {
+			let removed_by_you =
+				base_others.is_some_and(|b| b.contains(repo)) && !your_others.contains(repo);
+			if !your_others.contains(repo) && !removed_by_you {
+				self.changes.push(format!("added '{repo}' to {path}"));
+				others.push(repo.clone())
+			}
+		}
+
+		WgOrTfRepos {
+			main,
+			others: if others.is_empty() {
+				None
+			} else {
+				Some(others)
+			},
+		}
+	}
+
+	/// Merge a single value; if both sides changed it differently, keep yours and note a conflict
+	fn merge_value<T: Clone + PartialEq>(
+		&mut self,
+		path: &str,
+		base: Option<&T>,
+		yours: &T,
+		upstream: &T,
+		describe: impl Fn(&T) -> String,
+	) -> T {
+		if yours == upstream || base == Some(upstream) {
+			yours.clone()
+		} else if base == Some(yours) {
+			self.changes.push(format!(
+				"changed {path} from {} to {}",
+				describe(yours),
+				describe(upstream)
+			));
+			upstream.clone()
+		} else {
+			self.conflicts.push(Conflict {
+				path: path.to_string(),
+				yours: describe(yours),
+				upstream: describe(upstream),
+			});
+			yours.clone()
+		}
+	}
+}
+
+fn describe_horizontal_review(horizontal_review: &Option<HorizontalReview>) -> String {
+	match horizontal_review {
+		Some(HorizontalReview { specs, comments }) => {
+			format!("specs: '{specs}', comments: '{comments}'")
+		}
+		None => String::from("(none)"),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn groups(json: &str) -> Groups {
+		serde_json::from_str(json).unwrap()
+	}
+
+	const BASE: &str = r#"{
+		"apa": {
+			"workingGroup": { "main": "w3c/apa" },
+			"taskForces": { "rq": { "main": "w3c/rqtf", "others": ["w3c/raur"] } }
+		}
+	}"#;
+
+	#[test]
+	fn keeps_user_additions_and_picks_up_upstream_ones() {
+		let yours = groups(
+			r#"{
+			"apa": {
+				"workingGroup": { "main": "w3c/apa" },
+				"taskForces": {
+					"rq": { "main": "w3c/rqtf", "others": ["w3c/raur", "w3c/mine"] },
+					"new": { "main": "w3c/new-tf" }
+				}
+			},
+			"xyz": { "workingGroup": { "main": "w3c/xyz" }, "taskForces": {} }
+		}"#,
+		);
+		let upstream = groups(
+			r#"{
+			"ag": { "workingGroup": { "main": "w3c/wcag" }, "taskForces": {} },
+			"apa": {
+				"workingGroup": { "main": "w3c/apa", "others": ["w3c/captcha-accessibility"] },
+				"taskForces": { "rq": { "main": "w3c/rqtf", "others": ["w3c/raur", "w3c/saur"] } }
+			}
+		}"#,
+		);
+		let expected = groups(
+			r#"{
+			"ag": { "workingGroup": { "main": "w3c/wcag" }, "taskForces": {} },
+			"apa": {
+				"workingGroup": { "main": "w3c/apa", "others": ["w3c/captcha-accessibility"] },
+				"taskForces": {
+					"rq": { "main": "w3c/rqtf", "others": ["w3c/raur", "w3c/mine", "w3c/saur"] },
+					"new": { "main": "w3c/new-tf" }
+				}
+			},
+			"xyz": { "workingGroup": { "main": "w3c/xyz" }, "taskForces": {} }
+		}"#,
+		);
+
+		let (merged, report) = merge_groups(Some(&groups(BASE)), &yours, &upstream);
+		assert!(merged == expected);
+		assert_eq!(report.conflicts, vec![]);
+		assert_eq!(
+			report.changes,
+			vec![
+				String::from("added 'w3c/captcha-accessibility' to apa WG"),
+				String::from("added 'w3c/saur' to apa/rq TF"),
+				String::from("added WG 'ag'"),
+			]
+		);
+	}
+
+	#[test]
+	fn upstream_changes_and_removals_apply_to_untouched_values() {
+		let upstream = groups(
+			r#"{
+			"apa": {
+				"workingGroup": { "main": "w3c/apa-wg" },
+				"taskForces": { "rq": { "main": "w3c/rqtf" } }
+			}
+		}"#,
+		);
+
+		let (merged, report) = merge_groups(Some(&groups(BASE)), &groups(BASE), &upstream);
+		assert!(merged == upstream);
+		assert_eq!(report.conflicts, vec![]);
+		assert_eq!(report.changes.len(), 2);
+	}
+
+	#[test]
+	fn user_removals_are_kept() {
+		let yours = groups(
+			r#"{
+			"apa": {
+				"workingGroup": { "main": "w3c/apa" },
+				"taskForces": {}
+			}
+		}"#,
+		);
+
+		let (merged, report) = merge_groups(Some(&groups(BASE)), &yours, &groups(BASE));
+		assert!(merged == yours);
+		assert_eq!(report.changes, Vec::<String>::new());
+	}
+
+	#[test]
+	fn conflicting_changes_keep_yours() {
+		let yours = groups(
+			r#"{
+			"apa": {
+				"workingGroup": { "main": "w3c/apa-mine" },
+				"taskForces": { "rq": { "main": "w3c/rqtf", "others": ["w3c/raur"] } }
+			}
+		}"#,
+		);
+		let upstream = groups(
+			r#"{
+			"apa": {
+				"workingGroup": { "main": "w3c/apa-upstream" },
+				"taskForces": { "rq": { "main": "w3c/rqtf", "others": ["w3c/raur"] } }
+			}
+		}"#,
+		);
+
+		let (merged, report) = merge_groups(Some(&groups(BASE)), &yours, &upstream);
+		assert!(merged == yours);
+		assert_eq!(
+			report.conflicts,
+			vec![Conflict {
+				path: String::from("apa WG: main repo"),
+				yours: String::from("w3c/apa-mine"),
+				upstream: String::from("w3c/apa-upstream"),
+			}]
+		);
+	}
+
+	#[test]
+	fn changes_to_entries_removed_upstream_are_conflicts() {
+		let yours = groups(
+			r#"{
+			"apa": {
+				"workingGroup": { "main": "w3c/apa" },
+				"taskForces": { "rq": { "main": "w3c/rqtf", "others": ["w3c/raur", "w3c/mine"] } }
+			}
+		}"#,
+		);
+		let upstream =
+			groups(r#"{ "apa": { "workingGroup": { "main": "w3c/apa" }, "taskForces": {} } }"#);
+
+		let (merged, report) = merge_groups(Some(&groups(BASE)), &yours, &upstream);
+		assert!(merged == yours);
+		assert_eq!(report.changes, Vec::<String>::new());
+		assert_eq!(
+			report.conflicts,
+			vec![Conflict {
+				path: String::from("TF 'apa/rq'"),
+				yours: String::from("changed"),
+				upstream: String::from("removed"),
+			}]
+		);
+	}
+
+	#[test]
+	fn unknown_base_removes_nothing() {
+		let yours =
+			groups(r#"{ "xyz": { "workingGroup": { "main": "w3c/xyz" }, "taskForces": {} } }"#);
+
+		let (merged, report) = merge_groups(None, &yours, &groups(BASE));
+		assert_eq!(merged.len(), 2);
+		assert_eq!(report.changes, vec![String::from("added WG 'apa'")]);
+	}
+}
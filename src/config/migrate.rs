@@ -0,0 +1,200 @@
+use std::{fs, path::PathBuf};
+
+use chrono::Local;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+use super::{config_dir, ConfigError};
+
+/// Upgrades a config file's JSON from one version to another
+///
+/// Migrations are only needed when a file's _structure_ changes; if a version bump only changes
+/// its content (as with most shipped repos file updates), no migration is needed.
+pub(super) struct Migration {
+	pub from: u16,
+	pub to: u16,
+	pub apply: fn(&mut Value) -> Result<(), String>,
+}
+
+/// If the config file exists, and has an old structure, back it up, and upgrade it in place
+///
+/// Returns the version that the file was upgraded from (if it was).
+pub(super) fn migrate_file<T: DeserializeOwned + Serialize>(
+	file_name: &str,
+	migrations: &[Migration],
+) -> Result<Option<u16>, ConfigError> {
+	let file_path = config_dir().join(file_name);
+	if !file_path.exists() {
+		return Ok(None);
+	}
+
+	let json = fs::read_to_string(&file_path)?;
+	match migrate::<T>(file_name, &json, migrations)? {
+		Some((from, to, migrated)) => {
+			let backup_path = back_up(file_name)?;
+			fs::write(&file_path, migrated)?;
+			println!(
+				"Upgraded {file_name} from version {from} to {to} (backup saved as: {backup_path:?})"
+			);
+			Ok(Some(from))
+		}
+		None => Ok(None),
+	}
+}
+
+/// Read a config file, applying any migrations in memory (the file isn't changed)
+pub(super) fn read_migrated<T: DeserializeOwned + Serialize>(
+	file_name: &str,
+	migrations: &[Migration],
+) -> Result<String, ConfigError> {
+	let json = fs::read_to_string(config_dir().join(file_name))?;
+	Ok(match migrate::<T>(file_name, &json, migrations)? {
+		Some((_, _, migrated)) => migrated,
+		None => json,
+	})
+}
+
+/// Copy a config file, before changing it, to a backup named after the version in the file, and
+/// the time
+///
+/// Existing backups are never overwritten (if there's already one from this second, it's kept, as
+/// it has the earlier content).
+pub(super) fn back_up(file_name: &str) -> Result<PathBuf, ConfigError> {
+	let file_path = config_dir().join(file_name);
+	let value: Value = serde_json::from_str(&fs::read_to_string(&file_path)?).map_err(|error| {
+		ConfigError::JsonError {
+			file_name: file_name.to_string(),
+			details: error.to_string(),
+		}
+	})?;
+	let version =
+		version(&value).ok_or_else(|| ConfigError::JsonMissingVersion(file_name.into()))?;
+
+	let stamp = Local::now().format("%Y%m%dT%H%M%S");
+	let backup_path = config_dir().join(format!("{file_name}.v{version}.{stamp}.bak"));
+	if !backup_path.exists() {
+		fs::copy(file_path, &backup_path)?;
+	}
+	Ok(backup_path)
+}
+
+/// Apply any migrations that start from the JSON's version, in turn
+///
+/// Returns the original and new versions, and the migrated JSON, or None if no migrations
+/// applied. The result is checked by deserialising it.
+pub(super) fn migrate<T: DeserializeOwned + Serialize>(
+	file_name: &str,
+	json: &str,
+	migrations: &[Migration],
+) -> Result<Option<(u16, u16, String)>, ConfigError> {
+	let json_error = |details: String| ConfigError::JsonError {
+		file_name: file_name.to_string(),
+		details,
+	};
+
+	let mut value: Value =
+		serde_json::from_str(json).map_err(|error| json_error(error.to_string()))?;
+	let from = version(&value).ok_or_else(|| ConfigError::JsonMissingVersion(file_name.into()))?;
+
+	let mut current = from;
+	while let Some(migration) = migrations.iter().find(|m| m.from == current) {
+		(migration.apply)(&mut value).map_err(|details| {
+			json_error(format!(
+				"migrating from version {} to {}: {details}",
+				migration.from, migration.to
+			))
+		})?;
+		value["meta"]["version"] = Value::from(migration.to);
+		current = migration.to;
+	}
+
+	if current == from {
+		return Ok(None);
+	}
+
+	let migrated: T =
+		serde_json::from_value(value).map_err(|error| json_error(error.to_string()))?;
+	Ok(Some((
+		from,
+		current,
+		serde_json::to_string_pretty(&migrated).expect("should be able to serialise migrated JSON"),
+	)))
+}
+
+fn version(value: &Value) -> Option<u16> {
+	value["meta"]["version"]
+		.as_u64()
+		.and_then(|version| u16::try_from(version).ok())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde::Deserialize;
+
+	#[derive(Serialize, Deserialize)]
+	#[serde(deny_unknown_fields)]
+	struct Thing {
+		meta: Value,
+		name: String,
+		count: u32,
+	}
+
+	fn rename(value: &mut Value) -> Result<(), String> {
+		let old = value
+			.as_object_mut()
+			.and_then(|object| object.remove("title"))
+			.ok_or("missing \"title\"")?;
+		value["name"] = old;
+		Ok(())
+	}
+
+	fn add_count(value: &mut Value) -> Result<(), String> {
+		value["count"] = Value::from(0);
+		Ok(())
+	}
+
+	const MIGRATIONS: &[Migration] = &[
+		Migration {
+			from: 1,
+			to: 2,
+			apply: rename,
+		},
+		Migration {
+			from: 2,
+			to: 3,
+			apply: add_count,
+		},
+	];
+
+	#[test]
+	fn migrations_are_chained() {
+		let v1 = r#"{ "meta": { "version": 1 }, "title": "x" }"#;
+		let (from, to, json) = migrate::<Thing>("thing.json", v1, MIGRATIONS)
+			.unwrap()
+			.unwrap();
+		assert_eq!((from, to), (1, 3));
+		let thing: Thing = serde_json::from_str(&json).unwrap();
+		assert_eq!((thing.name.as_str(), thing.count), ("x", 0));
+		assert_eq!(thing.meta["version"], 3);
+	}
+
+	#[test]
+	fn current_and_unknown_versions_are_left_alone() {
+		let v3 = r#"{ "meta": { "version": 3 }, "name": "x", "count": 1 }"#;
+		assert!(migrate::<Thing>("thing.json", v3, MIGRATIONS)
+			.unwrap()
+			.is_none());
+		let v9 = r#"{ "meta": { "version": 9 }, "whatever": true }"#;
+		assert!(migrate::<Thing>("thing.json", v9, MIGRATIONS)
+			.unwrap()
+			.is_none());
+	}
+
+	#[test]
+	fn failed_migration_is_an_error() {
+		let v1 = r#"{ "meta": { "version": 1 }, "name": "x" }"#;
+		assert!(migrate::<Thing>("thing.json", v1, MIGRATIONS).is_err());
+		assert!(migrate::<Thing>("thing.json", r#"{ "meta": {} }"#, MIGRATIONS).is_err());
+	}
+}
@@ -1,36 +1,263 @@
-use std::collections::HashMap;
+use std::{collections::BTreeMap, fs, process::Command};
 
 use serde::{Deserialize, Serialize};
 
-use super::{config_dir, get_or_create, InitialContent, Meta};
+use super::merge::{merge_groups, MergeReport};
+use super::migrate::{back_up, migrate_file, read_migrated, Migration};
+use super::validate::{check_repos, is_valid_repo_name, validate_repos, Problem};
+use super::{config_dir, get_or_create, ConfigError, InitialContent, Meta};
 
 include!(concat!(env!("OUT_DIR"), "/repos_constants.rs"));
 
+/// The shipped version of the repos file that the user's file is based on; used for merging
+const BASE_FILE_NAME: &str = "repos-base.json";
+
+/// Structural changes to the repos file, by version (none yet: so far, new versions have only
+/// changed the content, which is handled by [`Repos::upgrade()`])
+///
+/// The version in the file is its content version, so a migration's `to` is the content version
+/// that the new structure first shipped with.
+const MIGRATIONS: &[Migration] = &[];
+
+/// The content version that the repos file's current structure first shipped with (only changed
+/// along with a new migration)
+#[cfg(test)]
+const STRUCTURE_VERSION: u16 = 1;
+
 /// Holds all information on WGs, the TFs they contain, and the repos belonging to all of them.
-#[derive(Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct Repos {
 	meta: Meta,
-	repos: HashMap<String, WorkingGroupInfo>,
+	repos: BTreeMap<String, WorkingGroupInfo>,
 }
 
 impl Repos {
 	/// Load an existing repos JSON file, or create and save one, and then return it
 	pub fn load_or_init() -> Result<Self, super::ConfigError> {
-		get_or_create(
+		migrate_file::<Self>(FILE_NAME, MIGRATIONS)?;
+		let repos: Repos = get_or_create(
 			FILE_NAME,
 			"repos",
 			InitialContent::Static(DEFAULT_REPOS.to_string()),
 			CURRENT_VERSION,
 			"NOTE",
-			Some(format!("You may want to contribute any additions you made, and then delete your {:?} file (a new version will be written on the next run).", config_dir().join(FILE_NAME)))
-		)
+			Some(String::from("Run 'nt config upgrade-repos' to merge any additions you made with the latest version (use '--dry-run' to preview the changes). You may also want to contribute your additions.")),
+			Some(validate_repos),
+		)?;
+
+		// If the user's file is current, it's based on the shipped one.
+		let base_path = config_dir().join(BASE_FILE_NAME);
+		if !base_path.exists() && repos.meta.version.to_string() == CURRENT_VERSION {
+			fs::write(base_path, DEFAULT_REPOS)?;
+		}
+
+		Ok(repos)
+	}
+
+	/// Merge the user's repos file with the shipped (upstream) one, and save it (unless in dry-run
+	/// mode)
+	///
+	/// User-added WGs, TFs and repos are kept, and upstream additions are picked up. If a value
+	/// has been changed both by the user and upstream, the user's value is kept, and the conflict
+	/// is reported.
+	pub fn upgrade(dry_run: bool) -> Result<MergeReport, ConfigError> {
+		let yours = parse(FILE_NAME, &read_migrated::<Self>(FILE_NAME, MIGRATIONS)?)?;
+		let upstream = parse(FILE_NAME, DEFAULT_REPOS).expect("shipped repos file should be valid");
+
+		let base_path = config_dir().join(BASE_FILE_NAME);
+		let base = if base_path.exists() {
+			Some(parse(
+				BASE_FILE_NAME,
+				&read_migrated::<Self>(BASE_FILE_NAME, MIGRATIONS)?,
+			)?)
+			.filter(|base| base.meta.version == yours.meta.version)
+		} else {
+			None
+		};
+
+		let (merged, report) = merge_groups(
+			base.as_ref().map(|b| &b.repos),
+			&yours.repos,
+			&upstream.repos,
+		);
+
+		if !dry_run {
+			let backup_path = back_up(FILE_NAME)?;
+			println!("Backed up {FILE_NAME} as: {backup_path:?}");
+			let upgraded = Repos {
+				meta: upstream.meta,
+				repos: merged,
+			};
+			fs::write(
+				config_dir().join(FILE_NAME),
+				serde_json::to_string_pretty(&upgraded).expect("should be able to serialise repos"),
+			)?;
+			fs::write(base_path, DEFAULT_REPOS)?;
+		}
+
+		Ok(report)
+	}
+
+	/// Check the repos JSON file in the config directory, and return all problems found with it
+	///
+	/// This doesn't create the file if it doesn't exist.
+	pub fn check() -> Result<Vec<Problem>, ConfigError> {
+		// Any problems with old structures that can't be migrated will be reported by the check.
+		let json_string = read_migrated::<Self>(FILE_NAME, MIGRATIONS)
+			.or_else(|_| fs::read_to_string(config_dir().join(FILE_NAME)))?;
+		Ok(check_repos(&json_string).1)
+	}
+
+	// NOTE: Assumes that the dir and file exist, because this will be called after load_or_init()
+	/// Save the repos, after backing up the existing file
+	pub fn save(&self) {
+		back_up(FILE_NAME)
+			.unwrap_or_else(|error| panic!("should be able to back up {FILE_NAME}: {error}"));
+		std::fs::write(config_dir().join(FILE_NAME), self.to_json())
+			.unwrap_or_else(|_| panic!("should be able to write {FILE_NAME}"));
+	}
+
+	/// Check the repos as they would be saved, and return any problems found
+	pub fn problems(&self) -> Vec<Problem> {
+		validate_repos(self, &self.to_json())
+	}
+
+	fn to_json(&self) -> String {
+		serde_json::to_string_pretty(&self).expect("should be able to serialise repos")
+	}
+
+	/// Add a WG, with its main repo, and no TFs
+	pub fn add_wg(&mut self, wg: &str, main: &str) -> Result<(), ConfigError> {
+		check_repo_name(main)?;
+		if self.is_known_wg(wg) {
+			return Err(ConfigError::Edit(format!("WG '{wg}' already exists")));
+		}
+
+		self.repos.insert(
+			wg.to_string(),
+			WorkingGroupInfo {
+				horizontal_review: None,
+				working_group: WgOrTfRepos::new(main),
+				task_forces: BTreeMap::new(),
+			},
+		);
+		Ok(())
+	}
+
+	/// Add a WG, with its (and its TFs') repo info
+	pub fn add_wg_info(&mut self, wg: &str, info: WorkingGroupInfo) -> Result<(), ConfigError> {
+		if self.is_known_wg(wg) {
+			return Err(ConfigError::Edit(format!("WG '{wg}' already exists")));
+		}
+
+		self.repos.insert(wg.to_string(), info);
+		Ok(())
+	}
+
+	/// Add a TF, with its main repo, to a WG
+	pub fn add_tf(&mut self, wg: &str, tf: &str, main: &str) -> Result<(), ConfigError> {
+		check_repo_name(main)?;
+		let info = self.wg_mut(wg)?;
+		if info.task_forces.contains_key(tf) {
+			return Err(ConfigError::Edit(format!(
+				"TF '{tf}' already exists in WG '{wg}'"
+			)));
+		}
+
+		info.task_forces
+			.insert(tf.to_string(), WgOrTfRepos::new(main));
+		Ok(())
+	}
+
+	/// Add an "other" repo to a WG (or one of its TFs, if given)
+	pub fn add_repo(&mut self, wg: &str, tf: Option<&str>, repo: &str) -> Result<(), ConfigError> {
+		check_repo_name(repo)?;
+		let team = self.team_mut(wg, tf)?;
+		if team.has_repo(repo) {
+			return Err(ConfigError::Edit(format!("'{repo}' is already listed")));
+		}
+
+		team.others
+			.get_or_insert_with(Vec::new)
+			.push(repo.to_string());
+		Ok(())
+	}
+
+	/// Remove an "other" repo from a WG (or one of its TFs, if given)
+	pub fn remove_repo(
+		&mut self,
+		wg: &str,
+		tf: Option<&str>,
+		repo: &str,
+	) -> Result<(), ConfigError> {
+		let team = self.team_mut(wg, tf)?;
+		if team.main == repo {
+			return Err(ConfigError::Edit(format!(
+				"'{repo}' is the main repo; use 'set-main' to change it"
+			)));
+		}
+
+		let others = team.others.take().unwrap_or_default();
+		if !others.iter().any(|other| other == repo) {
+			team.others = Some(others).filter(|o| !o.is_empty());
+			return Err(ConfigError::Edit(format!("'{repo}' is not listed")));
+		}
+
+		let remaining: Vec<String> = others.into_iter().filter(|other| other != repo).collect();
+		team.others = Some(remaining).filter(|o| !o.is_empty());
+		Ok(())
+	}
+
+	/// Set the main repo of a WG (or one of its TFs, if given)
+	///
+	/// If the new main repo was one of the "other" repos, it's removed from them.
+	pub fn set_main(&mut self, wg: &str, tf: Option<&str>, repo: &str) -> Result<(), ConfigError> {
+		check_repo_name(repo)?;
+		let team = self.team_mut(wg, tf)?;
+		if let Some(others) = team.others.take() {
+			let remaining: Vec<String> = others.into_iter().filter(|other| other != repo).collect();
+			team.others = Some(remaining).filter(|o| !o.is_empty());
+		}
+		team.main = repo.to_string();
+		Ok(())
+	}
+
+	fn wg_mut(&mut self, wg: &str) -> Result<&mut WorkingGroupInfo, ConfigError> {
+		self.repos
+			.get_mut(wg)
+			.ok_or_else(|| ConfigError::Edit(format!("Unknown WG name: '{wg}'")))
+	}
+
+	fn team_mut(&mut self, wg: &str, tf: Option<&str>) -> Result<&mut WgOrTfRepos, ConfigError> {
+		let info = self.wg_mut(wg)?;
+		match tf {
+			Some(tf) => info
+				.task_forces
+				.get_mut(tf)
+				.ok_or_else(|| ConfigError::Edit(format!("No TF called '{tf}' in WG '{wg}'"))),
+			None => Ok(&mut info.working_group),
+		}
+	}
+
+	/// Return the name of the repos JSON file
+	pub fn file_name() -> &'static str {
+		FILE_NAME
 	}
 
-	pub fn wgs_repos(&self) -> &HashMap<String, WorkingGroupInfo> {
+	pub fn wgs_repos(&self) -> &BTreeMap<String, WorkingGroupInfo> {
 		&self.repos
 	}
 
+	pub(super) fn wgs_repos_mut(&mut self) -> &mut BTreeMap<String, WorkingGroupInfo> {
+		&mut self.repos
+	}
+
+	#[cfg(test)]
+	pub(super) fn shipped() -> Self {
+		serde_json::from_str(DEFAULT_REPOS).unwrap()
+	}
+
 	pub fn known_wg_names(&self) -> Vec<&String> {
 		self.repos.keys().collect()
 	}
@@ -41,27 +268,191 @@ impl Repos {
 	}
 }
 
+/// Check, using `gh`, whether the given repo exists (and is visible to the user)
+pub fn repo_exists(repo: &str) -> bool {
+	Command::new("gh")
+		.args(["repo", "view", repo, "--json", "name"])
+		.output()
+		.expect("'gh' should run")
+		.status
+		.success()
+}
+
+fn check_repo_name(repo: &str) -> Result<(), ConfigError> {
+	if is_valid_repo_name(repo) {
+		Ok(())
+	} else {
+		Err(ConfigError::Edit(format!(
+			"'{repo}' is not in 'owner/repo' format"
+		)))
+	}
+}
+
+fn parse(file_name: &str, json: &str) -> Result<Repos, ConfigError> {
+	serde_json::from_str(json).map_err(|error| ConfigError::JsonError {
+		file_name: file_name.to_string(),
+		details: error.to_string(),
+	})
+}
+
 /// Contains horizontal review (if applicable), and repo info for a WG, as well as repo info for
 /// the WG's contained TFs.
-#[derive(Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct WorkingGroupInfo {
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub horizontal_review: Option<HorizontalReview>,
 	pub working_group: WgOrTfRepos,
-	pub task_forces: HashMap<String, WgOrTfRepos>,
+	pub task_forces: BTreeMap<String, WgOrTfRepos>,
+}
+
+impl WorkingGroupInfo {
+	/// Whether the given repo is one of the WG's, or its TFs', main or other repos
+	pub fn has_repo(&self, repo: &str) -> bool {
+		self.working_group.has_repo(repo) || self.task_forces.values().any(|tf| tf.has_repo(repo))
+	}
+
+	/// The teams that the given repo belongs to: "WG" for the WG itself, and "<name> TF" for TFs
+	pub fn teams_with_repo(&self, repo: &str) -> Vec<String> {
+		let mut teams: Vec<String> = vec![];
+		if self.working_group.has_repo(repo) {
+			teams.push(String::from("WG"))
+		}
+		for (name, task_force) in &self.task_forces {
+			if task_force.has_repo(repo) {
+				teams.push(format!("{name} TF"))
+			}
+		}
+		teams
+	}
 }
 
 /// Provides URLs for the horizontal review repos for a WG
-#[derive(Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct HorizontalReview {
 	pub specs: String,
 	pub comments: String,
 }
 
 /// Provides URLs for the main and other repos for a WG or TF
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct WgOrTfRepos {
 	pub main: String,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub others: Option<Vec<String>>,
 }
+
+impl WgOrTfRepos {
+	fn new(main: &str) -> Self {
+		WgOrTfRepos {
+			main: main.to_string(),
+			others: None,
+		}
+	}
+
+	/// Whether the given repo is the main, or one of the other, repos
+	pub fn has_repo(&self, repo: &str) -> bool {
+		self.main == repo
+			|| self
+				.others
+				.as_ref()
+				.is_some_and(|others| others.iter().any(|other| other == repo))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::super::migrate::migrate;
+	use super::*;
+
+	fn repos() -> Repos {
+		Repos::shipped()
+	}
+
+	/// Every format the repos file has had, which should all load after migration
+	const HISTORICAL: &[(u16, &str)] = &[(1, include_str!("historical/repos-v1.json"))];
+
+	#[test]
+	fn historical_formats_load() {
+		for (version, json) in HISTORICAL {
+			let json = match migrate::<Repos>(FILE_NAME, json, MIGRATIONS).unwrap() {
+				Some((from, _, migrated)) => {
+					assert_eq!(from, *version);
+					migrated
+				}
+				None => json.to_string(),
+			};
+			let (repos, problems) = check_repos(&json);
+			let repos = repos.unwrap_or_else(|| panic!("version {version}: {problems:?}"));
+			// Content-only versions don't need migrating, so the version may be older than the
+			// shipped one, but the structure must be current.
+			assert!(
+				repos.meta.version >= STRUCTURE_VERSION,
+				"version {version} migrated to {}",
+				repos.meta.version
+			);
+		}
+	}
+
+	#[test]
+	fn structure_version_is_current() {
+		let latest_migration = MIGRATIONS.iter().map(|m| m.to).max().unwrap_or(1);
+		assert_eq!(STRUCTURE_VERSION, latest_migration);
+		assert!(repos().meta.version >= STRUCTURE_VERSION);
+	}
+
+	#[test]
+	fn teams_for_repo() {
+		let repos = repos();
+		let apa = &repos.wgs_repos()["apa"];
+		assert_eq!(apa.teams_with_repo("w3c/apa"), vec!["WG"]);
+		assert_eq!(apa.teams_with_repo("w3c/rqtf"), vec!["rq TF"]);
+		assert!(apa.teams_with_repo("w3c/nope").is_empty());
+	}
+
+	#[test]
+	fn add_wg_and_tf() {
+		let mut repos = repos();
+		repos.add_wg("xyz", "w3c/xyz").unwrap();
+		repos.add_tf("xyz", "abc", "w3c/abc").unwrap();
+		assert!(repos.wgs_repos()["xyz"].has_repo("w3c/abc"));
+		assert!(repos.add_wg("xyz", "w3c/xyz").is_err());
+		assert!(repos.add_tf("nope", "abc", "w3c/abc").is_err());
+		assert_eq!(repos.meta.version.to_string(), CURRENT_VERSION);
+	}
+
+	#[test]
+	fn add_and_remove_repo() {
+		let mut repos = repos();
+		repos.add_repo("apa", Some("fast"), "w3c/fast-2").unwrap();
+		assert!(repos.wgs_repos()["apa"].task_forces["fast"].has_repo("w3c/fast-2"));
+		assert!(repos.add_repo("apa", Some("fast"), "w3c/fast-2").is_err());
+
+		repos
+			.remove_repo("apa", Some("fast"), "w3c/fast-2")
+			.unwrap();
+		assert!(repos.wgs_repos()["apa"].task_forces["fast"]
+			.others
+			.is_none());
+		assert!(repos.remove_repo("apa", Some("fast"), "w3c/fast").is_err());
+		assert!(repos.remove_repo("apa", None, "w3c/nope").is_err());
+	}
+
+	#[test]
+	fn set_main_from_others() {
+		let mut repos = repos();
+		repos
+			.set_main("apa", None, "w3c/captcha-accessibility")
+			.unwrap();
+		let wg = &repos.wgs_repos()["apa"].working_group;
+		assert_eq!(wg.main, "w3c/captcha-accessibility");
+		assert!(wg.others.is_none());
+	}
+
+	#[test]
+	fn invalid_repo_name() {
+		assert!(repos().add_repo("apa", None, "w3c apa").is_err())
+	}
+}
@@ -1,9 +1,14 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
-use super::{config_dir, get_input, get_or_create, InitialContent, Meta};
+use super::layers::WG_VAR;
+use super::migrate::{back_up, migrate_file, Migration};
+use super::{config_dir, get_input, get_or_create, ConfigError, InitialContent, Meta, Prompting};
 
 const FILE_NAME: &str = "settings.json";
-const CURRENT_VERSION: u16 = 1;
+const CURRENT_VERSION: u16 = 2;
 
 /// Holds user settings
 #[derive(Serialize, Deserialize)]
@@ -16,23 +21,31 @@ pub struct Settings {
 #[serde(rename_all = "camelCase")]
 struct UserSettings {
 	working_group: String,
+	/// Named views: saved sub-command invocations (arguments as they'd be given to nt)
+	#[serde(default)]
+	views: BTreeMap<String, Vec<String>>,
 }
 
 impl Settings {
 	/// Load an existing settings JSON file, or create and save one, with the user's input, and then return it
-	pub fn load_or_init() -> Result<Self, super::ConfigError> {
+	pub fn load_or_init(valid_wgs: &[&String], prompting: Prompting) -> Result<Self, ConfigError> {
+		migrate_file::<Self>(FILE_NAME, MIGRATIONS)?;
 		get_or_create(
 			FILE_NAME,
 			"settings",
-			InitialContent::Creator(init),
+			InitialContent::Creator(Box::new(|| init(valid_wgs, prompting))),
 			format!("{CURRENT_VERSION}").as_str(),
 			"WARNING",
 			None,
+			None,
 		)
 	}
 
 	// NOTE: Assumes that the dir and file exist, because this will be called after get_settings()
+	/// Save the settings, after backing up the existing file
 	pub fn save(&self) {
+		back_up(FILE_NAME)
+			.unwrap_or_else(|error| panic!("should be able to back up {FILE_NAME}: {error}"));
 		std::fs::write(
 			config_dir().join(FILE_NAME),
 			serde_json::to_string_pretty(&self).expect("should be able to serialise settings"),
@@ -40,6 +53,11 @@ impl Settings {
 		.unwrap_or_else(|_| panic!("should be able to write {FILE_NAME}"));
 	}
 
+	/// Return the name of the settings JSON file
+	pub fn file_name() -> &'static str {
+		FILE_NAME
+	}
+
 	pub fn wg(&self) -> &String {
 		&self.conf.working_group
 	}
@@ -55,34 +73,147 @@ impl Settings {
 			println!("Default WG is now '{}'", &self.conf.working_group)
 		} else {
 			println!("Unknown WG name: '{wg}' - not changing setting");
-			return;
 		}
 	}
+
+	/// Return the named views
+	pub fn views(&self) -> &BTreeMap<String, Vec<String>> {
+		&self.conf.views
+	}
+
+	/// Save a named view (replacing any existing one with the same name)
+	pub fn save_view(&mut self, name: String, args: Vec<String>) {
+		self.conf.views.insert(name, args);
+		self.save()
+	}
+
+	/// Remove a named view; returns false if there was no such view
+	pub fn remove_view(&mut self, name: &str) -> bool {
+		let removed = self.conf.views.remove(name).is_some();
+		if removed {
+			self.save()
+		}
+		removed
+	}
+}
+
+/// Structural changes to the settings file, by version
+const MIGRATIONS: &[Migration] = &[Migration {
+	from: 1,
+	to: 2,
+	apply: add_views,
+}];
+
+/// Version 2 added named views
+fn add_views(settings: &mut Value) -> Result<(), String> {
+	settings["conf"]
+		.as_object_mut()
+		.ok_or("missing \"conf\" object")?
+		.entry("views")
+		.or_insert_with(|| Value::Object(Default::default()));
+	Ok(())
 }
 
-fn init() -> String {
+fn init(valid_wgs: &[&String], prompting: Prompting) -> Result<String, ConfigError> {
+	let working_group = match prompting {
+		Prompting::Interactive => get_group(valid_wgs)?,
+		Prompting::NonInteractive(given) => given_group(given, valid_wgs)?,
+	};
+
 	let initial = Settings {
 		meta: Meta {
 			version: CURRENT_VERSION,
 		},
 		conf: UserSettings {
-			working_group: get_group(),
+			working_group,
+			views: BTreeMap::new(),
 		},
 	};
 
-	serde_json::to_string_pretty(&initial).expect("should be able to serialise Settings")
+	Ok(serde_json::to_string_pretty(&initial).expect("should be able to serialise Settings"))
 }
 
-fn get_group() -> String {
+fn get_group(valid_wgs: &[&String]) -> Result<String, ConfigError> {
 	let mut answer: String;
 
 	loop {
-		answer = get_input("Default working group?");
-		if !answer.is_empty() {
-			// FIXME: check for valid group
+		answer = get_input("Default working group?")?;
+		if valid_wgs.contains(&&answer) {
 			break;
 		}
+		if !answer.is_empty() {
+			println!(
+				"Unknown WG name: '{answer}' - known WGs are: {}",
+				list(valid_wgs)
+			)
+		}
+	}
+
+	Ok(answer)
+}
+
+/// Use the (first) WG given on the command line (or in the environment) as the default WG
+fn given_group(given: Option<&str>, valid_wgs: &[&String]) -> Result<String, ConfigError> {
+	let wg = given
+		.and_then(|list| list.split(',').map(str::trim).find(|name| !name.is_empty()))
+		.ok_or_else(|| {
+			ConfigError::NonInteractive(format!(
+				"No settings file yet, so a default WG is needed: use --working-group/-g or set {WG_VAR}",
+			))
+		})?;
+
+	if valid_wgs.iter().any(|valid| valid.as_str() == wg) {
+		Ok(wg.to_string())
+	} else {
+		Err(ConfigError::NonInteractive(format!(
+			"Unknown WG name given for the settings file: '{wg}' - known WGs are: {}",
+			list(valid_wgs)
+		)))
+	}
+}
+
+fn list(valid_wgs: &[&String]) -> String {
+	valid_wgs
+		.iter()
+		.map(|wg| wg.as_str())
+		.collect::<Vec<_>>()
+		.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::super::migrate::migrate;
+	use super::*;
+
+	#[test]
+	fn given_group_must_be_known() {
+		let (apa, ag) = (String::from("apa"), String::from("ag"));
+		let valid = [&apa, &ag];
+		assert_eq!(given_group(Some("ag,apa"), &valid).unwrap(), "ag");
+		assert!(given_group(Some("xyz"), &valid).is_err());
+		assert!(given_group(None, &valid).is_err());
 	}
 
-	answer
+	/// Every format the settings file has had, which should all load after migration
+	const HISTORICAL: &[(u16, &str)] = &[
+		(1, include_str!("historical/settings-v1.json")),
+		(2, include_str!("historical/settings-v2.json")),
+	];
+
+	#[test]
+	fn historical_formats_load() {
+		assert_eq!(HISTORICAL.last().unwrap().0, CURRENT_VERSION);
+		for (version, json) in HISTORICAL {
+			let json = match migrate::<Settings>(FILE_NAME, json, MIGRATIONS).unwrap() {
+				Some((from, to, migrated)) => {
+					assert_eq!((from, to), (*version, CURRENT_VERSION));
+					migrated
+				}
+				None => json.to_string(),
+			};
+			let settings: Settings = serde_json::from_str(&json).unwrap();
+			assert_eq!(settings.meta.version, CURRENT_VERSION);
+			assert_eq!(settings.wg(), "apa");
+		}
+	}
 }
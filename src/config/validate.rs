@@ -0,0 +1,358 @@
+use std::{collections::BTreeMap, fmt};
+
+use regex::Regex;
+
+use super::repos::{HorizontalReview, Repos, WgOrTfRepos};
+
+#[derive(Debug, PartialEq)]
+pub enum Severity {
+	Error,
+	Warning,
+}
+
+/// A problem found in a config file, with its (1-based) position in the file
+#[derive(Debug, PartialEq)]
+pub struct Problem {
+	pub severity: Severity,
+	pub line: usize,
+	pub column: usize,
+	pub message: String,
+}
+
+impl fmt::Display for Problem {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let severity = match self.severity {
+			Severity::Error => "ERROR",
+			Severity::Warning => "WARNING",
+		};
+		write!(
+			f,
+			"{severity}: line {}, column {}: {}",
+			self.line, self.column, self.message
+		)
+	}
+}
+
+/// Parse and check repos JSON, returning the parsed repos (if the JSON is structurally valid) and
+/// any problems found.
+///
+/// Structural problems (such as syntax errors, missing or unknown keys) are reported by serde;
+/// further checks are made for repo syntax, group naming conventions, and duplicate repos within
+/// a WG (including its TFs).
+pub fn check_repos(json: &str) -> (Option<Repos>, Vec<Problem>) {
+	match serde_json::from_str::<Repos>(json) {
+		Ok(repos) => {
+			let problems = validate_repos(&repos, json);
+			(Some(repos), problems)
+		}
+		Err(error) => (
+			None,
+			vec![Problem {
+				severity: Severity::Error,
+				line: error.line(),
+				column: error.column(),
+				message: strip_position(&error.to_string()),
+			}],
+		),
+	}
+}
+
+const REPO_SYNTAX: &str = r"^[A-Za-z0-9][A-Za-z0-9-]*/[A-Za-z0-9._-]+$";
+
+/// Whether the given string is in "owner/repo" format
+pub(super) fn is_valid_repo_name(repo: &str) -> bool {
+	Regex::new(REPO_SYNTAX).unwrap().is_match(repo)
+}
+
+pub(super) fn validate_repos(repos: &Repos, json: &str) -> Vec<Problem> {
+	validate_groups(
+		repos.wgs_repos().iter().map(|(wg_name, info)| {
+			(
+				wg_name,
+				GroupRepos {
+					horizontal_review: info.horizontal_review.as_ref(),
+					working_group: Some(&info.working_group),
+					task_forces: &info.task_forces,
+				},
+			)
+		}),
+		json,
+	)
+}
+
+/// The parts of a WG's entry that are checked (in a project file, the WG's repos are optional)
+pub(super) struct GroupRepos<'a> {
+	pub horizontal_review: Option<&'a HorizontalReview>,
+	pub working_group: Option<&'a WgOrTfRepos>,
+	pub task_forces: &'a BTreeMap<String, WgOrTfRepos>,
+}
+
+/// Check WGs' repos, as given under the "repos" key in the JSON
+pub(super) fn validate_groups<'a>(
+	groups: impl Iterator<Item = (&'a String, GroupRepos<'a>)>,
+	json: &str,
+) -> Vec<Problem> {
+	let mut checker = Checker {
+		json,
+		problems: vec![],
+		repo_syntax: Regex::new(REPO_SYNTAX).unwrap(),
+	};
+	let repos_start = find_key(json, "repos", 0).unwrap_or(0);
+
+	let mut groups: Vec<(&String, GroupRepos)> = groups.collect();
+	groups.sort_by_key(|(name, _)| find_key(json, name, repos_start).unwrap_or(usize::MAX));
+
+	for (wg_name, info) in groups {
+		let wg_start = find_key(json, wg_name, repos_start).unwrap_or(repos_start);
+		checker.check_name(wg_name, "WG", wg_start);
+
+		let mut seen: Vec<&str> = vec![];
+		let mut cursor = wg_start;
+
+		if let Some(horizontal_review) = info.horizontal_review {
+			for repo in [&horizontal_review.specs, &horizontal_review.comments] {
+				cursor = checker.check_repo_syntax(repo, cursor);
+			}
+		}
+
+		if let Some(working_group) = info.working_group {
+			cursor = checker.check_team(working_group, wg_name, &mut seen, cursor);
+		}
+
+		for (tf_name, team) in in_document_order(json, info.task_forces.iter(), wg_start) {
+			let tf_start = find_key(json, tf_name, wg_start).unwrap_or(cursor);
+			checker.check_name(tf_name, "TF", tf_start);
+			cursor = checker.check_team(team, wg_name, &mut seen, tf_start);
+		}
+	}
+
+	checker.problems
+}
+
+struct Checker<'a> {
+	json: &'a str,
+	problems: Vec<Problem>,
+	repo_syntax: Regex,
+}
+
+impl<'a> Checker<'a> {
+	fn add(&mut self, severity: Severity, offset: usize, message: String) {
+		let (line, column) = position(self.json, offset);
+		self.problems.push(Problem {
+			severity,
+			line,
+			column,
+			message,
+		})
+	}
+
+	fn check_name(&mut self, name: &str, kind: &str, offset: usize) {
+		if name != name.to_lowercase() {
+			self.add(
+				Severity::Warning,
+				offset,
+				format!("{kind} name '{name}' should be lower-case"),
+			)
+		}
+
+		let lower = name.to_lowercase();
+		if lower.ends_with("wg") || lower.ends_with("tf") {
+			self.add(
+				Severity::Warning,
+				offset,
+				format!("{kind} name '{name}' shouldn't include 'wg' or 'tf'"),
+			)
+		}
+	}
+
+	/// Check the syntax of a repo string; return the offset at which it was found
+	fn check_repo_syntax(&mut self, repo: &str, from: usize) -> usize {
+		let offset = find_quoted(self.json, repo, from).unwrap_or(from);
+		if !self.repo_syntax.is_match(repo) {
+			self.add(
+				Severity::Error,
+				offset,
+				format!("'{repo}' is not in 'owner/repo' format"),
+			)
+		}
+		offset
+	}
+
+	fn check_team(
+		&mut self,
+		team: &'a WgOrTfRepos,
+		wg_name: &str,
+		seen: &mut Vec<&'a str>,
+		from: usize,
+	) -> usize {
+		let mut cursor = from;
+		for repo in std::iter::once(&team.main).chain(team.others.iter().flatten()) {
+			cursor = self.check_repo_syntax(repo, cursor);
+			if seen.contains(&repo.as_str()) {
+				self.add(
+					Severity::Warning,
+					cursor,
+					format!("'{repo}' is listed more than once for the '{wg_name}' WG"),
+				)
+			} else {
+				seen.push(repo)
+			}
+		}
+		cursor
+	}
+}
+
+/// Sort named entries by where their keys appear in the JSON, so problems are reported in order
+fn in_document_order<'a, T>(
+	json: &str,
+	entries: impl Iterator<Item = (&'a String, &'a T)>,
+	from: usize,
+) -> Vec<(&'a String, &'a T)> {
+	let mut sorted: Vec<(&String, &T)> = entries.collect();
+	sorted.sort_by_key(|(name, _)| find_key(json, name, from).unwrap_or(usize::MAX));
+	sorted
+}
+
+fn find_quoted(json: &str, text: &str, from: usize) -> Option<usize> {
+	let needle = format!("\"{text}\"");
+	json.get(from..)
+		.and_then(|rest| rest.find(&needle))
+		.map(|index| from + index)
+}
+
+fn find_key(json: &str, key: &str, from: usize) -> Option<usize> {
+	let mut start = from;
+	while let Some(offset) = find_quoted(json, key, start) {
+		let after = offset + key.len() + 2;
+		if json[after..].trim_start().starts_with(':') {
+			return Some(offset);
+		}
+		start = after;
+	}
+	None
+}
+
+fn position(json: &str, offset: usize) -> (usize, usize) {
+	let before = &json[..offset.min(json.len())];
+	let line = before.matches('\n').count() + 1;
+	let column = before.chars().rev().take_while(|c| *c != '\n').count() + 1;
+	(line, column)
+}
+
+/// serde_json includes the position in its messages; we report it separately
+fn strip_position(message: &str) -> String {
+	match message.rfind(" at line ") {
+		Some(index) => message[..index].to_string(),
+		None => message.to_string(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const SHIPPED: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/repos.json"));
+
+	fn problems(json: &str) -> Vec<Problem> {
+		check_repos(json).1
+	}
+
+	#[test]
+	fn shipped_repos_are_valid() {
+		assert_eq!(problems(SHIPPED), vec![])
+	}
+
+	#[test]
+	fn bad_repo_syntax() {
+		let json = r#"{
+  "meta": { "version": 1 },
+  "repos": {
+    "apa": {
+      "workingGroup": { "main": "w3c apa" },
+      "taskForces": {}
+    }
+  }
+}"#;
+		assert_eq!(
+			problems(json),
+			vec![Problem {
+				severity: Severity::Error,
+				line: 5,
+				column: 33,
+				message: String::from("'w3c apa' is not in 'owner/repo' format")
+			}]
+		)
+	}
+
+	#[test]
+	fn group_naming_conventions() {
+		let json = r#"{
+  "meta": { "version": 1 },
+  "repos": {
+    "APA": {
+      "workingGroup": { "main": "w3c/apa" },
+      "taskForces": { "rqtf": { "main": "w3c/rqtf" } }
+    }
+  }
+}"#;
+		let found = problems(json);
+		assert_eq!(found.len(), 2);
+		assert_eq!(
+			found[0].message,
+			String::from("WG name 'APA' should be lower-case")
+		);
+		assert_eq!((found[0].line, found[0].column), (4, 5));
+		assert_eq!(
+			found[1].message,
+			String::from("TF name 'rqtf' shouldn't include 'wg' or 'tf'")
+		);
+		assert_eq!((found[1].line, found[1].column), (6, 23));
+	}
+
+	#[test]
+	fn duplicate_repo_within_group() {
+		let json = r#"{
+  "meta": { "version": 1 },
+  "repos": {
+    "apa": {
+      "workingGroup": { "main": "w3c/apa" },
+      "taskForces": { "rq": { "main": "w3c/rqtf", "others": ["w3c/apa"] } }
+    }
+  }
+}"#;
+		let found = problems(json);
+		assert_eq!(found.len(), 1);
+		assert_eq!(found[0].severity, Severity::Warning);
+		assert_eq!((found[0].line, found[0].column), (6, 62));
+	}
+
+	#[test]
+	fn shared_repo_between_groups_is_fine() {
+		let json = r#"{
+  "meta": { "version": 1 },
+  "repos": {
+    "ag": { "workingGroup": { "main": "w3c/wcag" }, "taskForces": { "coga": { "main": "w3c/coga" } } },
+    "apa": { "workingGroup": { "main": "w3c/apa" }, "taskForces": { "coga": { "main": "w3c/coga" } } }
+  }
+}"#;
+		assert_eq!(problems(json), vec![])
+	}
+
+	#[test]
+	fn unknown_key() {
+		let json = r#"{
+  "meta": { "version": 1 },
+  "repos": {
+    "apa": {
+      "workingGroup": { "main": "w3c/apa", "other": ["w3c/captcha-accessibility"] },
+      "taskForces": {}
+    }
+  }
+}"#;
+		let found = problems(json);
+		assert_eq!(found.len(), 1);
+		assert_eq!(found[0].severity, Severity::Error);
+		assert!(found[0].message.starts_with("unknown field `other`"));
+		assert_eq!(found[0].line, 5);
+	}
+}
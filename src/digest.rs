@@ -0,0 +1,385 @@
+use std::{fmt::Write, io, str::FromStr};
+
+use chrono::{Local, NaiveDate};
+
+use crate::comments::query_comment_requests;
+use crate::config::{config_dir, WorkingGroupInfo};
+use crate::filters::Filters;
+use crate::gh;
+use crate::issues_actions::{make_repo_args, query_actions, query_issues_in_repos};
+use crate::locator::Locator;
+use crate::specs::{review_due, review_spec};
+use crate::status::LabelStringList;
+
+mod diff;
+mod snapshot;
+
+use diff::{diff, Changes};
+use snapshot::{Record, Snapshot};
+
+const SNAPSHOTS_DIR: &str = "snapshots";
+
+/// How to write out the digest
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DigestFormat {
+	Markdown,
+	/// Plain text, with a subject line, ready to be pasted into an email
+	Email,
+}
+
+impl FromStr for DigestFormat {
+	type Err = String;
+
+	fn from_str(name: &str) -> Result<Self, Self::Err> {
+		match name {
+			"markdown" => Ok(DigestFormat::Markdown),
+			"email" => Ok(DigestFormat::Email),
+			_ => Err(String::from("expected one of: markdown, email")),
+		}
+	}
+}
+
+/// Report what changed in the WG's (and all its TFs') open actions, and its review and comment
+/// requests, since a stored snapshot; then store a snapshot of how things are now (unless `save`
+/// is false).
+///
+/// The snapshot compared against is the latest one taken on or before `since`, or the latest one
+/// of all if no date is given. Snapshots are kept in the config dir, one per WG per day. If there's
+/// no snapshot from on or before `since`, items created or closed since then are searched for
+/// instead (but status changes and moved due dates can't be found that way).
+pub fn digest(
+	group_name: &str,
+	repos: &WorkingGroupInfo,
+	since: &Option<NaiveDate>,
+	format: DigestFormat,
+	save: bool,
+	verbose: &bool,
+) -> io::Result<()> {
+	let dir = config_dir().join(SNAPSHOTS_DIR).join(group_name);
+	let current = take_snapshot(group_name, repos, verbose);
+
+	match Snapshot::latest(&dir, *since)? {
+		Some(previous) => {
+			if previous.truncated || current.truncated {
+				// Not on stdout, as with the note below.
+				eprintln!(
+					"Some items may be missing from the snapshots, so new and closed items \
+aren't included\n"
+				);
+			}
+			print!(
+				"{}",
+				render(
+					group_name,
+					&previous.taken,
+					&diff(&previous, &current),
+					format
+				)
+			)
+		}
+		None => match since {
+			Some(date) => {
+				// Not on stdout, so that the digest can still be pasted (or piped) as-is.
+				eprintln!(
+					"No snapshot for '{group_name}' from on or before {date}; \
+searching for items created or closed since then instead\n"
+				);
+				print!(
+					"{}",
+					render(
+						group_name,
+						&date.to_string(),
+						&search_since(group_name, repos, *date, verbose),
+						format
+					)
+				)
+			}
+			None => println!("No snapshots for '{group_name}' yet"),
+		},
+	}
+
+	if save {
+		current.save(&dir)?;
+		if *verbose {
+			println!("Saved snapshot for {}", current.taken)
+		}
+	}
+
+	Ok(())
+}
+
+fn take_snapshot(group_name: &str, repos: &WorkingGroupInfo, verbose: &bool) -> Snapshot {
+	let actions = query_group_actions(group_name, repos, &Filters::everything(), &false, verbose);
+
+	let (reviews, comments) = match &repos.horizontal_review {
+		Some(horizontal_review) => (
+			query_reviews(&horizontal_review.specs, &Filters::everything(), verbose),
+			query_comment_requests(
+				&horizontal_review.comments,
+				&LabelStringList::default(),
+				&None,
+				Some(gh::SEARCH_LIMIT),
+				verbose,
+			)
+			.into_iter()
+			.map(|request| Record {
+				locator: format!("{}#{}", horizontal_review.comments, request.tracking_number),
+				title: request.title,
+				due: None,
+				status: Some(request.status.to_string()).filter(|status| !status.is_empty()),
+			})
+			.collect(),
+		),
+		None => (vec![], vec![]),
+	};
+
+	let truncated = [&actions, &reviews, &comments]
+		.iter()
+		.any(|records| records.len() >= gh::SEARCH_LIMIT as usize);
+
+	Snapshot {
+		taken: Local::now().date_naive().to_string(),
+		actions,
+		reviews,
+		comments,
+		truncated,
+	}
+}
+
+/// Search for actions and review requests created, and actions closed, since the given date
+fn search_since(
+	group_name: &str,
+	repos: &WorkingGroupInfo,
+	since: NaiveDate,
+	verbose: &bool,
+) -> Changes {
+	let created = Filters {
+		created_since: Some(since),
+		..Filters::everything()
+	};
+	let closed = Filters {
+		closed_since: Some(since),
+		..Filters::everything()
+	};
+
+	Changes {
+		opened_actions: query_group_actions(group_name, repos, &created, &true, verbose),
+		closed_actions: query_group_actions(group_name, repos, &closed, &true, verbose),
+		new_reviews: match &repos.horizontal_review {
+			Some(horizontal_review) => query_reviews(&horizontal_review.specs, &created, verbose),
+			None => vec![],
+		},
+		..Default::default()
+	}
+}
+
+/// Query for the WG's and all its TFs' actions (open ones, unless `closed` is given)
+fn query_group_actions(
+	group_name: &str,
+	repos: &WorkingGroupInfo,
+	filters: &Filters,
+	closed: &bool,
+	verbose: &bool,
+) -> Vec<Record> {
+	query_actions(
+		&[(group_name, repos)],
+		&None,
+		closed,
+		&false,
+		&true,
+		&Some(vec![]),
+		filters,
+		verbose,
+	)
+	.into_iter()
+	.map(|dated| Record {
+		locator: format!(
+			"{}#{}",
+			dated.action.repository.name_with_owner, dated.action.number
+		),
+		title: dated.action.title,
+		due: dated.due.map(|due| due.to_string()),
+		status: None,
+	})
+	.collect()
+}
+
+/// Query for open spec review requests, including those whose due dates can't be worked out
+fn query_reviews(spec_repo: &str, filters: &Filters, verbose: &bool) -> Vec<Record> {
+	query_issues_in_repos(
+		make_repo_args(vec![spec_repo]),
+		&None,
+		&false,
+		&[],
+		filters,
+		"Spec review",
+		verbose,
+	)
+	.into_iter()
+	.map(|issue| Record {
+		locator: format!("{spec_repo}#{}", issue.number),
+		title: review_spec(&issue.title),
+		due: review_due(&issue.title).map(|due| due.to_string()),
+		status: None,
+	})
+	.collect()
+}
+
+fn render(group_name: &str, since: &str, changes: &Changes, format: DigestFormat) -> String {
+	let title = format!(
+		"{} digest: changes since {since}",
+		group_name.to_uppercase()
+	);
+	let mut out = match format {
+		DigestFormat::Markdown => format!("# {title}\n"),
+		DigestFormat::Email => format!("Subject: {title}\n"),
+	};
+
+	if changes.is_empty() {
+		out.push_str("\nNo changes.\n");
+		return out;
+	}
+
+	let describe = |record: &Record, detail: Option<String>| -> String {
+		let url = Locator::from_str(&record.locator)
+			.map(|locator| locator.url())
+			.unwrap_or_default();
+		let detail = detail.map(|text| format!(" ({text})")).unwrap_or_default();
+		match format {
+			DigestFormat::Markdown => {
+				format!("- [{}]({url}): {}{detail}", record.locator, record.title)
+			}
+			DigestFormat::Email => format!(
+				"  * {}: {}{detail}\n    {url}",
+				record.locator, record.title
+			),
+		}
+	};
+	let due = |record: &Record| record.due.clone().map(|date| format!("due {date}"));
+	let or_none = |value: &Option<String>| value.clone().unwrap_or(String::from("none"));
+
+	let mut section = |heading: &str, lines: Vec<String>| {
+		if lines.is_empty() {
+			return;
+		}
+		match format {
+			DigestFormat::Markdown => write!(out, "\n## {heading} ({})\n\n", lines.len()),
+			DigestFormat::Email => write!(out, "\n{heading} ({}):\n\n", lines.len()),
+		}
+		.unwrap();
+		for line in lines {
+			writeln!(out, "{line}").unwrap()
+		}
+	};
+
+	section(
+		"New actions",
+		changes
+			.opened_actions
+			.iter()
+			.map(|record| describe(record, due(record)))
+			.collect(),
+	);
+	section(
+		"Closed actions",
+		changes
+			.closed_actions
+			.iter()
+			.map(|record| describe(record, None))
+			.collect(),
+	);
+	section(
+		"New spec review requests",
+		changes
+			.new_reviews
+			.iter()
+			.map(|record| describe(record, due(record)))
+			.collect(),
+	);
+	section(
+		"Comment request status changes",
+		changes
+			.status_changes
+			.iter()
+			.map(|(before, after)| {
+				let change = format!("{} -> {}", or_none(&before.status), or_none(&after.status));
+				describe(after, Some(change))
+			})
+			.collect(),
+	);
+	section(
+		"Moved due dates",
+		changes
+			.moved_due_dates
+			.iter()
+			.map(|(before, after)| {
+				let change = format!("{} -> {}", or_none(&before.due), or_none(&after.due));
+				describe(after, Some(change))
+			})
+			.collect(),
+	);
+
+	out
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn record(locator: &str, due: Option<&str>) -> Record {
+		Record {
+			locator: locator.to_string(),
+			title: String::from("Do the thing"),
+			due: due.map(String::from),
+			status: None,
+		}
+	}
+
+	#[test]
+	fn formats() {
+		let changes = Changes {
+			opened_actions: vec![record("w3c/apa#3", Some("2024-02-01"))],
+			moved_due_dates: vec![(
+				record("w3c/apa#1", None),
+				record("w3c/apa#1", Some("2024-02-15")),
+			)],
+			..Default::default()
+		};
+
+		assert_eq!(
+			render("apa", "2024-01-01", &changes, DigestFormat::Markdown),
+			"# APA digest: changes since 2024-01-01
+
+## New actions (1)
+
+- [w3c/apa#3](https://github.com/w3c/apa/issues/3): Do the thing (due 2024-02-01)
+
+## Moved due dates (1)
+
+- [w3c/apa#1](https://github.com/w3c/apa/issues/1): Do the thing (none -> 2024-02-15)
+"
+		);
+		assert_eq!(
+			render("apa", "2024-01-01", &changes, DigestFormat::Email),
+			"Subject: APA digest: changes since 2024-01-01
+
+New actions (1):
+
+  * w3c/apa#3: Do the thing (due 2024-02-01)
+    https://github.com/w3c/apa/issues/3
+
+Moved due dates (1):
+
+  * w3c/apa#1: Do the thing (none -> 2024-02-15)
+    https://github.com/w3c/apa/issues/1
+"
+		);
+		assert!(render(
+			"apa",
+			"2024-01-01",
+			&Changes::default(),
+			DigestFormat::Email
+		)
+		.ends_with("\nNo changes.\n"));
+	}
+}
@@ -0,0 +1,188 @@
+use std::collections::BTreeMap;
+
+use super::snapshot::{Record, Snapshot};
+
+/// What changed between two snapshots
+#[derive(Debug, Default, PartialEq)]
+pub(super) struct Changes {
+	pub opened_actions: Vec<Record>,
+	pub closed_actions: Vec<Record>,
+	pub new_reviews: Vec<Record>,
+	/// Comment requests whose status changed, as (before, after)
+	pub status_changes: Vec<(Record, Record)>,
+	/// Actions and review requests whose due dates changed, as (before, after)
+	pub moved_due_dates: Vec<(Record, Record)>,
+}
+
+impl Changes {
+	pub fn is_empty(&self) -> bool {
+		*self == Changes::default()
+	}
+}
+
+/// Work out what changed from the `before` snapshot to the `after` one
+///
+/// If either snapshot may be missing items, new and closed items can't be told apart from ones
+/// that weren't returned, so only changes to items in both snapshots are given.
+pub(super) fn diff(before: &Snapshot, after: &Snapshot) -> Changes {
+	let added = |old: &[Record], new: &[Record]| -> Vec<Record> {
+		let old = by_locator(old);
+		new.iter()
+			.filter(|record| !old.contains_key(record.locator.as_str()))
+			.cloned()
+			.collect()
+	};
+
+	let changed = |old: &[Record], new: &[Record], field: fn(&Record) -> &Option<String>| {
+		let old = by_locator(old);
+		new.iter()
+			.filter_map(|record| {
+				old.get(record.locator.as_str())
+					.filter(|previous| field(previous) != field(record))
+					.map(|previous| ((*previous).clone(), record.clone()))
+			})
+			.collect::<Vec<(Record, Record)>>()
+	};
+
+	let due: fn(&Record) -> &Option<String> = |record| &record.due;
+	let mut moved_due_dates = changed(&before.actions, &after.actions, due);
+	moved_due_dates.extend(changed(&before.reviews, &after.reviews, due));
+
+	let status_changes = changed(&before.comments, &after.comments, |record| &record.status);
+
+	if before.truncated || after.truncated {
+		return Changes {
+			status_changes,
+			moved_due_dates,
+			..Default::default()
+		};
+	}
+
+	Changes {
+		opened_actions: added(&before.actions, &after.actions),
+		closed_actions: added(&after.actions, &before.actions),
+		new_reviews: added(&before.reviews, &after.reviews),
+		status_changes,
+		moved_due_dates,
+	}
+}
+
+fn by_locator(records: &[Record]) -> BTreeMap<&str, &Record> {
+	records
+		.iter()
+		.map(|record| (record.locator.as_str(), record))
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn record(locator: &str, due: Option<&str>, status: Option<&str>) -> Record {
+		Record {
+			locator: locator.to_string(),
+			title: String::from("Title"),
+			due: due.map(String::from),
+			status: status.map(String::from),
+		}
+	}
+
+	fn snapshot(actions: Vec<Record>, reviews: Vec<Record>, comments: Vec<Record>) -> Snapshot {
+		Snapshot {
+			taken: String::from("2024-01-01"),
+			actions,
+			reviews,
+			comments,
+			truncated: false,
+		}
+	}
+
+	#[test]
+	fn no_changes() {
+		let before = snapshot(
+			vec![record("w3c/apa#1", Some("2024-02-01"), None)],
+			vec![],
+			vec![record("w3c/a11y-request#5", None, Some("P"))],
+		);
+		assert!(diff(&before, &before).is_empty());
+	}
+
+	#[test]
+	fn all_kinds_of_change() {
+		let before = snapshot(
+			vec![
+				record("w3c/apa#1", Some("2024-02-01"), None),
+				record("w3c/apa#2", None, None),
+			],
+			vec![record("w3c/a11y-review#7", Some("2024-03-01"), None)],
+			vec![record("w3c/a11y-request#5", None, Some("P"))],
+		);
+		let after = snapshot(
+			vec![
+				record("w3c/apa#1", Some("2024-02-15"), None),
+				record("w3c/apa#3", None, None),
+			],
+			vec![
+				record("w3c/a11y-review#7", Some("2024-03-01"), None),
+				record("w3c/a11y-review#8", Some("2024-04-01"), None),
+			],
+			vec![record("w3c/a11y-request#5", None, Some("T A"))],
+		);
+
+		let changes = diff(&before, &after);
+		assert_eq!(
+			changes.opened_actions,
+			vec![record("w3c/apa#3", None, None)]
+		);
+		assert_eq!(
+			changes.closed_actions,
+			vec![record("w3c/apa#2", None, None)]
+		);
+		assert_eq!(
+			changes.new_reviews,
+			vec![record("w3c/a11y-review#8", Some("2024-04-01"), None)]
+		);
+		assert_eq!(
+			changes.status_changes,
+			vec![(
+				record("w3c/a11y-request#5", None, Some("P")),
+				record("w3c/a11y-request#5", None, Some("T A"))
+			)]
+		);
+		assert_eq!(
+			changes.moved_due_dates,
+			vec![(
+				record("w3c/apa#1", Some("2024-02-01"), None),
+				record("w3c/apa#1", Some("2024-02-15"), None)
+			)]
+		);
+	}
+
+	#[test]
+	fn truncated_snapshots_only_give_changes_to_items_in_both() {
+		let before = snapshot(
+			vec![
+				record("w3c/apa#1", Some("2024-02-01"), None),
+				record("w3c/apa#2", None, None),
+			],
+			vec![],
+			vec![],
+		);
+		let after = Snapshot {
+			truncated: true,
+			..snapshot(
+				vec![
+					record("w3c/apa#1", Some("2024-02-15"), None),
+					record("w3c/apa#3", None, None),
+				],
+				vec![],
+				vec![],
+			)
+		};
+
+		let changes = diff(&before, &after);
+		assert!(changes.opened_actions.is_empty());
+		assert!(changes.closed_actions.is_empty());
+		assert_eq!(changes.moved_due_dates.len(), 1);
+	}
+}
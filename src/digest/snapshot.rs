@@ -0,0 +1,116 @@
+use std::{fs, io, path::Path};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// What's known about an action, review request or comment request at the time of a snapshot
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(super) struct Record {
+	pub locator: String,
+	pub title: String,
+	pub due: Option<String>,
+	pub status: Option<String>,
+}
+
+/// A WG's open actions, review requests and comment requests, as of a given day
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub(super) struct Snapshot {
+	pub taken: String,
+	pub actions: Vec<Record>,
+	pub reviews: Vec<Record>,
+	pub comments: Vec<Record>,
+	/// Whether any of the queries returned as many results as GitHub's search will give, so
+	/// that some items may be missing
+	#[serde(default)]
+	pub truncated: bool,
+}
+
+impl Snapshot {
+	/// Save the snapshot in the given directory, replacing any other snapshot from the same day
+	pub fn save(&self, dir: &Path) -> io::Result<()> {
+		fs::create_dir_all(dir)?;
+		fs::write(
+			dir.join(format!("{}.json", self.taken)),
+			serde_json::to_string_pretty(self).expect("should be able to serialise snapshot"),
+		)
+	}
+
+	/// Load the latest snapshot in the given directory that was taken on or before `on_or_before`
+	/// (or the latest of all, if no date is given)
+	pub fn latest(dir: &Path, on_or_before: Option<NaiveDate>) -> io::Result<Option<Snapshot>> {
+		if !dir.exists() {
+			return Ok(None);
+		}
+
+		let mut latest: Option<(NaiveDate, _)> = None;
+		for entry in fs::read_dir(dir)? {
+			let path = entry?.path();
+			let date = path
+				.file_stem()
+				.and_then(|stem| stem.to_str())
+				.filter(|_| {
+					path.extension()
+						.is_some_and(|extension| extension == "json")
+				})
+				.and_then(|stem| NaiveDate::parse_from_str(stem, DATE_FORMAT).ok());
+
+			if let Some(date) = date {
+				let in_range = on_or_before.is_none_or(|limit| date <= limit);
+				let later = latest.as_ref().is_none_or(|(best, _)| date > *best);
+				if in_range && later {
+					latest = Some((date, path))
+				}
+			}
+		}
+
+		match latest {
+			Some((_, path)) => {
+				let snapshot = serde_json::from_str(&fs::read_to_string(&path)?)
+					.map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+				Ok(Some(snapshot))
+			}
+			None => Ok(None),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn snapshot(taken: &str) -> Snapshot {
+		Snapshot {
+			taken: taken.to_string(),
+			actions: vec![],
+			reviews: vec![],
+			comments: vec![],
+			truncated: false,
+		}
+	}
+
+	#[test]
+	fn latest_on_or_before() {
+		let dir = std::env::temp_dir().join(format!("nt-snapshots-{}", std::process::id()));
+		for taken in ["2024-01-01", "2024-01-08", "2024-01-15"] {
+			snapshot(taken).save(&dir).unwrap();
+		}
+		fs::write(dir.join("notes.txt"), "ignored").unwrap();
+
+		let date = |text| NaiveDate::parse_from_str(text, DATE_FORMAT).ok();
+		let taken = |limit| {
+			Snapshot::latest(&dir, limit)
+				.unwrap()
+				.map(|snapshot| snapshot.taken)
+		};
+
+		assert_eq!(taken(None), Some(String::from("2024-01-15")));
+		assert_eq!(taken(date("2024-01-10")), Some(String::from("2024-01-08")));
+		assert_eq!(taken(date("2024-01-08")), Some(String::from("2024-01-08")));
+		assert_eq!(taken(date("2023-12-31")), None);
+
+		fs::remove_dir_all(&dir).unwrap();
+		assert_eq!(taken(None), None);
+	}
+}
@@ -0,0 +1,191 @@
+use chrono::NaiveDate;
+
+use crate::gh;
+use crate::returned_issue::ReturnedIssue;
+
+/// Extra criteria for querying issues and actions
+///
+/// All of these are passed to `gh`. Where the returned data allows (labels, author, and
+/// creation/update dates) they're also checked against the returned actions. The milestone,
+/// mentions, search text and closing date can only be checked by GitHub, because the returned
+/// data doesn't include milestones, comments or closing dates.
+#[derive(Default)]
+pub struct Filters {
+	pub labels: Vec<String>,
+	pub milestone: Option<String>,
+	pub author: Option<String>,
+	pub mentions: Option<String>,
+	pub search: Option<String>,
+	pub updated_since: Option<NaiveDate>,
+	pub created_since: Option<NaiveDate>,
+	pub closed_since: Option<NaiveDate>,
+	/// The most results `gh` should return (if not given, it returns up to 30)
+	pub limit: Option<u32>,
+}
+
+impl Filters {
+	/// No criteria, but asking for as many results as GitHub will give, for reports that need to
+	/// see everything (such as reminders)
+	pub(crate) fn everything() -> Self {
+		Filters {
+			limit: Some(gh::SEARCH_LIMIT),
+			..Default::default()
+		}
+	}
+
+	/// Arguments for `gh search issues`
+	pub(crate) fn args(&self) -> Vec<String> {
+		let mut args: Vec<String> = vec![];
+
+		for label in &self.labels {
+			args.extend([String::from("--label"), label.clone()])
+		}
+		if let Some(milestone) = &self.milestone {
+			args.extend([String::from("--milestone"), milestone.clone()])
+		}
+		if let Some(author) = &self.author {
+			args.extend([String::from("--author"), author.clone()])
+		}
+		if let Some(mentions) = &self.mentions {
+			args.extend([String::from("--mentions"), mentions.clone()])
+		}
+		if let Some(date) = self.updated_since {
+			args.extend([String::from("--updated"), format!(">={date}")])
+		}
+		if let Some(date) = self.created_since {
+			args.extend([String::from("--created"), format!(">={date}")])
+		}
+		if let Some(date) = self.closed_since {
+			args.extend([String::from("--closed"), format!(">={date}")])
+		}
+		if let Some(limit) = self.limit {
+			args.extend([String::from("--limit"), limit.to_string()])
+		}
+		if let Some(search) = &self.search {
+			args.extend([String::from("--"), search.clone()])
+		}
+
+		args
+	}
+
+	/// Whether a returned issue meets the criteria that can be checked locally
+	pub(crate) fn matches(&self, issue: &ReturnedIssue) -> bool {
+		let has_labels = self
+			.labels
+			.iter()
+			.all(|wanted| issue.labels.iter().any(|label| &label.name == wanted));
+
+		// '@me' can only be resolved by GitHub.
+		let by_author = match &self.author {
+			Some(author) if author != "@me" => issue
+				.author
+				.as_ref()
+				.is_some_and(|issue_author| &issue_author.login == author),
+			_ => true,
+		};
+
+		has_labels
+			&& by_author
+			&& on_or_after(&issue.updated_at, self.updated_since)
+			&& on_or_after(&issue.created_at, self.created_since)
+	}
+}
+
+/// Whether a timestamp (e.g. "2023-05-23T12:34:56Z") is on or after the given date (if any)
+fn on_or_after(timestamp: &str, since: Option<NaiveDate>) -> bool {
+	match since {
+		Some(since) => timestamp
+			.get(..10)
+			.and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+			.is_none_or(|date| date >= since),
+		None => true,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn issue() -> ReturnedIssue {
+		serde_json::from_str(
+			r#"{
+				"assignees": [],
+				"author": { "login": "someone" },
+				"body": "due 23 May 2027",
+				"createdAt": "2023-05-01T09:00:00Z",
+				"labels": [{ "id": "1", "color": "fff", "description": "", "name": "action" }],
+				"number": 42,
+				"repository": { "name": "apa", "nameWithOwner": "w3c/apa" },
+				"state": "open",
+				"title": "Do the thing",
+				"updatedAt": "2023-06-01T09:00:00Z"
+			}"#,
+		)
+		.unwrap()
+	}
+
+	fn date(text: &str) -> Option<NaiveDate> {
+		NaiveDate::parse_from_str(text, "%Y-%m-%d").ok()
+	}
+
+	#[test]
+	fn args_for_gh() {
+		let filters = Filters {
+			labels: vec![String::from("a"), String::from("b")],
+			author: Some(String::from("@me")),
+			created_since: date("2023-05-01"),
+			closed_since: date("2023-06-01"),
+			search: Some(String::from("contrast ratio")),
+			limit: Some(100),
+			..Default::default()
+		};
+		assert_eq!(
+			filters.args(),
+			vec![
+				"--label",
+				"a",
+				"--label",
+				"b",
+				"--author",
+				"@me",
+				"--created",
+				">=2023-05-01",
+				"--closed",
+				">=2023-06-01",
+				"--limit",
+				"100",
+				"--",
+				"contrast ratio"
+			]
+		);
+	}
+
+	#[test]
+	fn local_matching() {
+		assert!(Filters::default().matches(&issue()));
+		assert!(Filters {
+			labels: vec![String::from("action")],
+			author: Some(String::from("@me")),
+			created_since: date("2023-05-01"),
+			updated_since: date("2023-06-01"),
+			..Default::default()
+		}
+		.matches(&issue()));
+
+		assert!(!Filters {
+			labels: vec![String::from("nope")],
+			..Default::default()
+		}
+		.matches(&issue()));
+		assert!(!Filters {
+			author: Some(String::from("someone-else")),
+			..Default::default()
+		}
+		.matches(&issue()));
+		assert!(!Filters {
+			updated_since: date("2023-06-02"),
+			..Default::default()
+		}
+		.matches(&issue()));
+	}
+}
@@ -1,5 +1,8 @@
 use crate::returned_issue::Assignee;
 
+/// Shown (and used in sorting) when nobody is assigned
+pub const UNASSIGNED: &str = "UNASSIGNED";
+
 pub fn flatten_assignees(assignees: &[Assignee]) -> String {
 	let logins = assignees
 		.iter()
@@ -8,7 +11,7 @@ pub fn flatten_assignees(assignees: &[Assignee]) -> String {
 		.join(",");
 
 	if logins.is_empty() {
-		String::from("UNASSIGNED")
+		String::from(UNASSIGNED)
 	} else {
 		logins
 	}
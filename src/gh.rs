@@ -0,0 +1,45 @@
+//! Running `gh` for JSON results
+use std::{process::Command, str};
+
+use serde::de::DeserializeOwned;
+
+/// The most results GitHub's search will give
+pub(crate) const SEARCH_LIMIT: u32 = 1000;
+
+/// Run a `gh` command, and parse its JSON output
+///
+/// If `gh` can't be run, or fails, the error gives its output, so that the caller can decide how
+/// to report it.
+pub(crate) fn run_json<T: DeserializeOwned>(cmd: &mut Command) -> Result<T, String> {
+	let output = cmd
+		.output()
+		.map_err(|error| format!("couldn't run 'gh': {error}"))?;
+
+	if !output.status.success() {
+		return Err(format!(
+			"'gh' did not run successfully: {}{}",
+			String::from_utf8_lossy(&output.stdout),
+			String::from_utf8_lossy(&output.stderr)
+		)
+		.trim_end()
+		.to_string());
+	}
+
+	let out = str::from_utf8(&output.stdout).map_err(|_| "got non-utf8 data from 'gh'")?;
+	serde_json::from_str(out).map_err(|error| format!("couldn't parse output from 'gh': {error}"))
+}
+
+/// Report an error from [`run_json()`], and stop (as the sub-commands do)
+pub(crate) fn fail<T>(error: String) -> T {
+	eprintln!("{error}");
+	panic!("'gh' did not run successfully")
+}
+
+/// Note (on stderr) when a query returned as many results as it asked for, as some may be missing
+pub(crate) fn note_if_limited(description: &str, count: usize, limit: u32) {
+	if count >= limit as usize {
+		eprintln!(
+			"Note: {description}: only the first {limit} results were returned; there may be more"
+		)
+	}
+}
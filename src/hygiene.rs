@@ -0,0 +1,191 @@
+use chrono::{Days, Local, NaiveDate};
+
+use crate::comments::{query_comment_requests, CommentReviewRequest};
+use crate::config::WorkingGroupInfo;
+use crate::filters::Filters;
+use crate::flatten_assignees::UNASSIGNED;
+use crate::gh;
+use crate::issues_actions::{
+	get_query_repos_args, query_actions, query_issues_in_repos, selection_fail, DatedAction,
+};
+use crate::status::LabelStringList;
+use crate::table_row::{render, SortBy, TableOptions, TableRow};
+
+/// Things that need tidying up, by category
+struct Findings {
+	unassigned_actions: Vec<DatedAction>,
+	undated_actions: Vec<DatedAction>,
+	overdue_actions: Vec<DatedAction>,
+}
+
+impl Findings {
+	fn new(actions: &[DatedAction], overdue_before: NaiveDate) -> Self {
+		let matching = |predicate: &dyn Fn(&DatedAction) -> bool| -> Vec<DatedAction> {
+			actions
+				.iter()
+				.filter(|dated| predicate(dated))
+				.cloned()
+				.collect()
+		};
+
+		Findings {
+			unassigned_actions: matching(&|dated| dated.action.assignees.is_empty()),
+			undated_actions: matching(&|dated| dated.due.is_none()),
+			overdue_actions: matching(&|dated| dated.due.is_some_and(|due| due < overdue_before)),
+		}
+	}
+}
+
+/// Report on things that chairs may want to tidy up in the WG's and all TFs' repos
+///
+/// This lists unassigned actions, actions with no due date, actions that are more than
+/// `overdue_days` overdue, and issues (of any kind) with no activity in `stale_days`. If the
+/// group is a horizontal review group, unassigned comment requests are listed too.
+pub fn hygiene(
+	group_name: &str,
+	repos: &WorkingGroupInfo,
+	stale_days: u64,
+	overdue_days: u64,
+	verbose: &bool,
+) {
+	let today = Local::now().date_naive();
+	let groups = [(group_name, repos)];
+	let all_tfs = Some(vec![]);
+
+	let actions = query_actions(
+		&groups,
+		&None,
+		&false,
+		&false,
+		&true,
+		&all_tfs,
+		&Filters::everything(),
+		verbose,
+	);
+	let findings = Findings::new(&actions, today - Days::new(overdue_days));
+
+	println!(
+		"Hygiene report for the '{group_name}' WG ({} open actions)\n",
+		actions.len()
+	);
+
+	let action_columns = ["due", "locator", "title", "assignees"];
+	section(
+		"Unassigned actions",
+		findings.unassigned_actions,
+		&action_columns,
+		None,
+	);
+	section(
+		"Actions with no due date",
+		findings.undated_actions,
+		&action_columns,
+		None,
+	);
+	section(
+		&format!("Actions more than {overdue_days} days overdue"),
+		findings.overdue_actions,
+		&action_columns,
+		None,
+	);
+
+	if let Some(horizontal_review) = &repos.horizontal_review {
+		let unassigned: Vec<CommentReviewRequest> = query_comment_requests(
+			&horizontal_review.comments,
+			&LabelStringList::default(),
+			&None,
+			Some(gh::SEARCH_LIMIT),
+			verbose,
+		)
+		.into_iter()
+		.filter(|request| request.tracking_assignees == UNASSIGNED)
+		.collect();
+		section(
+			"Unassigned comment requests",
+			unassigned,
+			&["id", "title", "spec", "status"],
+			None,
+		);
+	}
+
+	let stale_before = format!("<{}", today - Days::new(stale_days));
+	let stale: Vec<DatedAction> = query_issues_in_repos(
+		get_query_repos_args(&groups, &false, &true, &all_tfs).unwrap_or_else(selection_fail),
+		&None,
+		&false,
+		&["--updated", &stale_before],
+		&Filters::everything(),
+		"Stale issues",
+		verbose,
+	)
+	.into_iter()
+	.map(DatedAction::from)
+	.collect();
+	section(
+		&format!("Issues with no activity in {stale_days} days"),
+		stale,
+		&["updated", "locator", "title", "assignees"],
+		Some(SortBy::Updated),
+	);
+}
+
+fn section<T: TableRow>(heading: &str, rows: Vec<T>, columns: &[&str], sort: Option<SortBy>) {
+	println!("{heading}: {}\n", rows.len());
+	if rows.is_empty() {
+		return;
+	}
+
+	let options = TableOptions {
+		sort,
+		..Default::default()
+	};
+	match render(rows, columns, &options, &[]) {
+		Ok(table) => println!("{table}\n"),
+		Err(message) => println!("{message}"),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn dated(assignees: &str, due: Option<NaiveDate>) -> DatedAction {
+		let action = serde_json::from_str(&format!(
+			r#"{{
+				"assignees": [{assignees}],
+				"author": null,
+				"body": "",
+				"createdAt": "",
+				"labels": [],
+				"number": 1,
+				"repository": {{ "name": "apa", "nameWithOwner": "w3c/apa" }},
+				"state": "open",
+				"title": "",
+				"updatedAt": ""
+			}}"#
+		))
+		.unwrap();
+		DatedAction {
+			action,
+			due,
+			group: None,
+		}
+	}
+
+	#[test]
+	fn findings() {
+		let someone = r#"{ "id": "", "is_bot": false, "login": "a", "type": "", "url": "" }"#;
+		let date = |day| NaiveDate::from_ymd_opt(2023, 5, day);
+		let actions = vec![
+			dated("", date(1)),
+			dated(someone, None),
+			dated(someone, date(20)),
+		];
+
+		let findings = Findings::new(&actions, date(10).unwrap());
+		assert_eq!(findings.unassigned_actions.len(), 1);
+		assert_eq!(findings.undated_actions.len(), 1);
+		assert_eq!(findings.overdue_actions.len(), 1);
+		assert_eq!(findings.overdue_actions[0].due, date(1));
+	}
+}
@@ -1,126 +1,438 @@
-use std::io::{self, Write};
-use std::{println, process::Command, str};
+use std::{
+	collections::{BTreeMap, BTreeSet},
+	fmt, println,
+	process::Command,
+	str,
+	str::FromStr,
+};
 
-use chrono::NaiveDate;
+use chrono::{Local, NaiveDate};
 use regex::Regex;
 
 use crate::config::{WgOrTfRepos, WorkingGroupInfo};
-use crate::flatten_assignees::flatten_assignees;
-use crate::make_table::make_table;
+use crate::filters::Filters;
+use crate::flatten_assignees::{flatten_assignees, UNASSIGNED};
+use crate::gh;
+use crate::locator::Locator;
+use crate::minutes::{minutes_summary, SummaryStyle};
 use crate::returned_issue::ReturnedIssue;
 use crate::showing::showing;
+use crate::table_row::{
+	check_columns, due_cell, render_sorted, sort_rows, SortBy, SortKey, TableOptions, TableRow,
+};
 
-struct DatedAction {
-	action: ReturnedIssue,
-	due: Option<NaiveDate>,
+#[derive(Clone)]
+pub(crate) struct DatedAction {
+	pub action: ReturnedIssue,
+	pub due: Option<NaiveDate>,
+	/// The group(s) that the action's repo belongs to, when querying several groups
+	pub group: Option<String>,
+}
+
+impl From<ReturnedIssue> for DatedAction {
+	fn from(action: ReturnedIssue) -> Self {
+		DatedAction {
+			due: get_due(&action.body),
+			action,
+			group: None,
+		}
+	}
 }
 
 impl DatedAction {
-	// TODO: Make trait?
-	fn to_vec_string(&self) -> Vec<String> {
-		vec![
-			match self.due {
-				Some(date) => format!("{date}"),
-				None => String::from("(no date)"),
-			},
-			format!(
+	fn locator(&self) -> Locator {
+		Locator::from_name_with_owner(&self.action.repository.name_with_owner, self.action.number)
+			.expect("should be able to make a Locator from returned issue")
+	}
+}
+
+impl TableRow for DatedAction {
+	const COLUMNS: &'static [&'static str] =
+		&["group", "due", "locator", "title", "assignees", "updated"];
+
+	fn cell(&self, column: &str) -> String {
+		match column {
+			"group" => self.group.clone().unwrap_or_default(),
+			"due" => due_cell(self.due),
+			"locator" => format!(
 				"{}#{}",
 				self.action.repository.name_with_owner, self.action.number
 			),
-			self.action.title.to_string(),
-			flatten_assignees(&self.action.assignees),
-		]
+			"title" => self.action.title.to_string(),
+			"assignees" => flatten_assignees(&self.action.assignees),
+			"updated" => self.action.updated_at.chars().take(10).collect(),
+			_ => unreachable!("unknown column '{column}'"),
+		}
+	}
+
+	fn sort_key(&self, by: SortBy) -> SortKey {
+		match by {
+			SortBy::Due => SortKey::date(self.due),
+			SortBy::Repo => SortKey::Text(self.action.repository.name_with_owner.to_string()),
+			SortBy::Assignee => SortKey::Text(flatten_assignees(&self.action.assignees)),
+			SortBy::Number => SortKey::Number(self.action.number),
+			SortBy::Updated => SortKey::Text(self.action.updated_at.to_string()),
+		}
 	}
 }
 
-/// Query for issues in given repos; have `gh` print the output.
+/// Query for issues in given groups' repos; have `gh` print the output.
+#[allow(clippy::too_many_arguments)]
 pub fn issues(
-	repos: &WorkingGroupInfo,
+	groups: &[(&str, &WorkingGroupInfo)],
 	actions: &bool,
 	assignee: &Option<String>,
 	closed: &bool,
 	main: &bool,
 	wg: &bool,
 	tf: &Option<Vec<String>>,
+	filters: &Filters,
 	verbose: &bool,
 ) {
 	let mut cmd = Command::new("gh");
-	add_base_args(repos, &mut cmd, assignee, closed, main, wg, tf);
+	add_base_args(groups, &mut cmd, assignee, closed, main, wg, tf);
 
 	let action_args: Vec<&str> = if *actions {
 		vec!["--label", "action"]
 	} else {
 		vec![]
 	};
+	// The filter args go last, as they may end with search text.
+	cmd.args(action_args).args(filters.args());
 
 	if *verbose {
 		println!("Issues: running: {cmd:?}");
 	}
-	cmd.args(action_args).status().expect("'gh' should run");
+	cmd.status().expect("'gh' should run");
 }
 
-/// Query for action issues in given repos; make a custom report, sorted by due date.
+/// Query for action issues in given groups' repos; make a custom report, sorted by due date
+/// (unless another sort order is given).
+///
+/// If more than one group is given, a column showing the group(s) for each action is included.
+/// Instead of a table, a summary for meeting minutes (or IRC) can be given. The listed actions are
+/// returned, so that they can be opened.
 // TODO: DRY with specs, comments?
+#[allow(clippy::too_many_arguments)]
 pub fn actions(
-	repos: &WorkingGroupInfo,
+	groups: &[(&str, &WorkingGroupInfo)],
 	assignee: &Option<String>,
 	closed: &bool,
 	main: &bool,
 	wg: &bool,
 	tf: &Option<Vec<String>>,
+	filters: &Filters,
+	table_options: &TableOptions,
+	group_by: &Option<GroupBy>,
+	summary: &Option<SummaryStyle>,
 	verbose: &bool,
-) {
-	let mut cmd = Command::new("gh");
-	add_base_args(repos, &mut cmd, assignee, closed, main, wg, tf);
-	cmd.args(["--label", "action"])
-		.args(["--json", &ReturnedIssue::FIELD_NAMES_AS_ARRAY.join(",")]);
+) -> Vec<Locator> {
+	if let Err(message) = check_columns::<DatedAction>(table_options) {
+		println!("{message}");
+		return vec![];
+	}
 
-	if *verbose {
-		println!("Actions: running: {cmd:?}");
-	}
-	let output = cmd.output().expect("'gh' should run");
-
-	if output.status.success() {
-		let out = str::from_utf8(&output.stdout).expect("got non-utf8 data from 'gh'");
-		let actions: Vec<ReturnedIssue> = serde_json::from_str(out).unwrap();
-
-		if actions.is_empty() {
-			// TODO: Make this neater a la .join() for the vec
-			println!(
-				"No actions found (WG: {}; TFs: {:?})",
-				wg,
-				tf.as_ref().unwrap_or(&Vec::<String>::new())
-			);
-			return;
-		} else {
-			println!("{} actions\n", showing(actions.len()))
-		}
-
-		let mut dated_actions: Vec<DatedAction> = vec![];
-		for action in actions {
-			dated_actions.push(DatedAction {
-				action: action.clone(), // TODO: idiomatic?
-				due: get_due(&action.body),
-			})
+	let mut dated_actions = query_actions(groups, assignee, closed, main, wg, tf, filters, verbose);
+
+	if dated_actions.is_empty() {
+		// TODO: Make this neater a la .join() for the vec
+		println!(
+			"No actions found (WG: {}; TFs: {:?})",
+			wg,
+			tf.as_ref().unwrap_or(&Vec::<String>::new())
+		);
+		return vec![];
+	}
+
+	if let Some(style) = summary {
+		print!(
+			"{}",
+			minutes_summary(&dated_actions, *style, Local::now().date_naive())
+		);
+		return dated_actions.iter().map(DatedAction::locator).collect();
+	}
+
+	println!("{} actions\n", showing(dated_actions.len()));
+
+	let multiple_groups = groups.len() > 1;
+	if multiple_groups {
+		for dated in &mut dated_actions {
+			dated.group = Some(groups_for_repo(
+				groups,
+				&dated.action.repository.name_with_owner,
+			))
 		}
-		dated_actions.sort_by_key(|a| a.due);
+	}
 
-		let mut rows: Vec<Vec<String>> = vec![];
-		for dated in dated_actions {
-			rows.push(dated.to_vec_string())
+	let mut columns = vec!["due", "locator", "title", "assignees"];
+	if multiple_groups {
+		columns.insert(0, "group")
+	}
+
+	let by = match group_by {
+		Some(by) => *by,
+		None => {
+			sort_rows(&mut dated_actions, table_options);
+			match render_sorted(&dated_actions, &columns, table_options, &[]) {
+				Ok(table) => println!("{table}"),
+				Err(message) => println!("{message}"),
+			}
+			return dated_actions.iter().map(DatedAction::locator).collect();
 		}
+	};
 
-		let table = make_table(vec!["DUE", "LOCATOR", "TITLE", "ASSIGNEES"], rows, None);
-		println!("{table}")
-	} else {
-		io::stdout().write_all(&output.stdout).unwrap();
-		io::stderr().write_all(&output.stderr).unwrap();
-		panic!("'gh' did not run successfully")
+	// Actions are listed in the order shown; those in more than one section are listed once.
+	let mut listed: Vec<Locator> = vec![];
+	let today = Local::now().date_naive();
+	for (name, mut section) in sections(dated_actions, by, groups) {
+		println!("{name}: {}\n", Subtotals::of(&section, today));
+		sort_rows(&mut section, table_options);
+		match render_sorted(&section, &columns, table_options, &[]) {
+			Ok(table) => println!("{table}\n"),
+			Err(message) => println!("{message}"),
+		}
+		for locator in section.iter().map(DatedAction::locator) {
+			if !listed.contains(&locator) {
+				listed.push(locator)
+			}
+		}
+	}
+
+	listed
+}
+
+/// How to divide the actions report into sections
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GroupBy {
+	Tf,
+	Repo,
+	Assignee,
+}
+
+impl FromStr for GroupBy {
+	type Err = String;
+
+	fn from_str(name: &str) -> Result<Self, Self::Err> {
+		match name {
+			"tf" => Ok(GroupBy::Tf),
+			"repo" => Ok(GroupBy::Repo),
+			"assignee" => Ok(GroupBy::Assignee),
+			_ => Err(String::from("expected one of: tf, repo, assignee")),
+		}
+	}
+}
+
+/// Counts of the actions in a section of the report
+#[derive(Debug, PartialEq)]
+struct Subtotals {
+	open: usize,
+	overdue: usize,
+	undated: usize,
+	closed: usize,
+}
+
+impl Subtotals {
+	fn of(actions: &[DatedAction], today: NaiveDate) -> Self {
+		let open: Vec<&DatedAction> = actions
+			.iter()
+			.filter(|dated| dated.action.state == "open")
+			.collect();
+		Subtotals {
+			open: open.len(),
+			overdue: open
+				.iter()
+				.filter(|dated| dated.due.is_some_and(|due| due < today))
+				.count(),
+			undated: open.iter().filter(|dated| dated.due.is_none()).count(),
+			closed: actions.len() - open.len(),
+		}
+	}
+}
+
+impl fmt::Display for Subtotals {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"{} open ({} overdue, {} undated)",
+			self.open, self.overdue, self.undated
+		)?;
+		if self.closed > 0 {
+			write!(f, ", {} closed", self.closed)?
+		}
+		Ok(())
+	}
+}
+
+/// Divide the actions into named sections, keeping their order within each section
+///
+/// Actions with more than one assignee (or whose repo belongs to more than one TF) appear in
+/// each relevant section.
+fn sections(
+	actions: Vec<DatedAction>,
+	by: GroupBy,
+	groups: &[(&str, &WorkingGroupInfo)],
+) -> BTreeMap<String, Vec<DatedAction>> {
+	let mut sections: BTreeMap<String, Vec<DatedAction>> = BTreeMap::new();
+
+	for dated in actions {
+		let repo = &dated.action.repository.name_with_owner;
+		let mut names: BTreeSet<String> = match by {
+			GroupBy::Tf => groups
+				.iter()
+				.flat_map(|(group_name, info)| {
+					info.teams_with_repo(repo)
+						.into_iter()
+						.map(move |team| match groups.len() {
+							1 => team,
+							_ => format!("{group_name} {team}"),
+						})
+				})
+				.collect(),
+			GroupBy::Repo => BTreeSet::from([repo.to_string()]),
+			GroupBy::Assignee => dated
+				.action
+				.assignees
+				.iter()
+				.map(|assignee| assignee.login.to_string())
+				.collect(),
+		};
+
+		if names.is_empty() {
+			names.insert(String::from(match by {
+				GroupBy::Assignee => UNASSIGNED,
+				_ => "(other)",
+			}));
+		}
+
+		for name in names {
+			sections.entry(name).or_default().push(dated.clone())
+		}
+	}
+
+	sections
+}
+
+fn groups_for_repo(groups: &[(&str, &WorkingGroupInfo)], repo: &str) -> String {
+	groups
+		.iter()
+		.filter(|(_, info)| info.has_repo(repo))
+		.map(|(name, _)| *name)
+		.collect::<Vec<_>>()
+		.join(",")
+}
+
+/// Query for action issues in given groups' repos, and return them with their due dates, sorted
+/// by due date.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn query_actions(
+	groups: &[(&str, &WorkingGroupInfo)],
+	assignee: &Option<String>,
+	closed: &bool,
+	main: &bool,
+	wg: &bool,
+	tf: &Option<Vec<String>>,
+	filters: &Filters,
+	verbose: &bool,
+) -> Vec<DatedAction> {
+	query_actions_in_repos(
+		get_query_repos_args(groups, main, wg, tf).unwrap_or_else(selection_fail),
+		assignee,
+		closed,
+		filters,
+		verbose,
+	)
+}
+
+/// Query for action issues, given pre-computed `--repo` arguments for `gh`, and return them with
+/// their due dates, sorted by due date.
+pub(crate) fn query_actions_in_repos(
+	query_repo_args: Vec<String>,
+	assignee: &Option<String>,
+	closed: &bool,
+	filters: &Filters,
+	verbose: &bool,
+) -> Vec<DatedAction> {
+	try_query_actions_in_repos(query_repo_args, assignee, closed, filters, verbose)
+		.unwrap_or_else(gh::fail)
+}
+
+/// As [`query_actions_in_repos()`], but if `gh` fails, the error is returned, rather than stopping
+pub(crate) fn try_query_actions_in_repos(
+	query_repo_args: Vec<String>,
+	assignee: &Option<String>,
+	closed: &bool,
+	filters: &Filters,
+	verbose: &bool,
+) -> Result<Vec<DatedAction>, String> {
+	let mut dated_actions: Vec<DatedAction> = try_query_issues_in_repos(
+		query_repo_args,
+		assignee,
+		closed,
+		&["--label", "action"],
+		filters,
+		"Actions",
+		verbose,
+	)?
+	.into_iter()
+	.map(DatedAction::from)
+	.collect();
+	dated_actions.sort_by_key(|a| (a.due.is_none(), a.due));
+	Ok(dated_actions)
+}
+
+/// Query for issues, given pre-computed `--repo` arguments, and any other arguments, for `gh`;
+/// return those that match the filters.
+pub(crate) fn query_issues_in_repos(
+	query_repo_args: Vec<String>,
+	assignee: &Option<String>,
+	closed: &bool,
+	extra_args: &[&str],
+	filters: &Filters,
+	description: &str,
+	verbose: &bool,
+) -> Vec<ReturnedIssue> {
+	try_query_issues_in_repos(
+		query_repo_args,
+		assignee,
+		closed,
+		extra_args,
+		filters,
+		description,
+		verbose,
+	)
+	.unwrap_or_else(gh::fail)
+}
+
+fn try_query_issues_in_repos(
+	query_repo_args: Vec<String>,
+	assignee: &Option<String>,
+	closed: &bool,
+	extra_args: &[&str],
+	filters: &Filters,
+	description: &str,
+	verbose: &bool,
+) -> Result<Vec<ReturnedIssue>, String> {
+	let mut cmd = Command::new("gh");
+	add_base_args_for_repos(&mut cmd, query_repo_args, assignee, closed);
+	cmd.args(extra_args)
+		.args(["--json", &ReturnedIssue::FIELD_NAMES_AS_ARRAY.join(",")])
+		.args(filters.args());
+
+	if *verbose {
+		println!("{description}: running: {cmd:?}");
 	}
+	let issues: Vec<ReturnedIssue> = gh::run_json(&mut cmd)?;
+	if let Some(limit) = filters.limit {
+		gh::note_if_limited(description, issues.len(), limit);
+	}
+	Ok(issues
+		.into_iter()
+		.filter(|issue| filters.matches(issue))
+		.collect())
 }
 
 fn add_base_args(
-	repos: &WorkingGroupInfo,
+	groups: &[(&str, &WorkingGroupInfo)],
 	command: &mut Command,
 	assignee: &Option<String>,
 	closed: &bool,
@@ -128,7 +440,16 @@ fn add_base_args(
 	wg: &bool,
 	tf: &Option<Vec<String>>,
 ) {
-	let query_repo_args = get_query_repos_args(repos, main, wg, tf);
+	let query_repo_args = get_query_repos_args(groups, main, wg, tf).unwrap_or_else(selection_fail);
+	add_base_args_for_repos(command, query_repo_args, assignee, closed)
+}
+
+fn add_base_args_for_repos(
+	command: &mut Command,
+	query_repo_args: Vec<String>,
+	assignee: &Option<String>,
+	closed: &bool,
+) {
 	let assignee_args: Vec<&str> = match assignee {
 		Some(user) => vec!["--assignee", user],
 		None => vec![],
@@ -146,38 +467,74 @@ fn add_base_args(
 		.args(closed_args);
 }
 
-fn get_query_repos_args(
-	repos: &WorkingGroupInfo,
+/// Work out the `--repo` arguments for `gh` for the given groups' WG and/or TF repos
+///
+/// Repos that are shared between the groups are only included once. A named TF must exist in at
+/// least one of the groups, and at least one repo must be selected; if not, the error says why.
+pub(crate) fn get_query_repos_args(
+	groups: &[(&str, &WorkingGroupInfo)],
 	main: &bool,
 	wg: &bool,
 	tf: &Option<Vec<String>>,
-) -> Vec<String> {
+) -> Result<Vec<String>, String> {
 	let mut query_repos: Vec<&str> = Vec::new();
 
-	if *wg {
-		add_repos_for_team(&mut query_repos, main, &repos.working_group)
-	}
+	for (_, repos) in groups {
+		if *wg {
+			add_repos_for_team(&mut query_repos, main, &repos.working_group)
+		}
 
-	if let Some(task_forces) = tf {
-		if task_forces.is_empty() {
-			for team_repos in repos.task_forces.values() {
-				add_repos_for_team(&mut query_repos, main, team_repos)
-			}
-		} else {
-			for task_force in task_forces {
-				if let Some(team_repos) = repos.task_forces.get(task_force) {
+		if let Some(task_forces) = tf {
+			if task_forces.is_empty() {
+				for team_repos in repos.task_forces.values() {
 					add_repos_for_team(&mut query_repos, main, team_repos)
-				} else {
-					panic!("No TF called '{}'—you may want to pass the TF option last on the command line. Known TFs for this WG are:\n{:?}", task_force, repos.task_forces.keys());
 				}
+			} else {
+				for task_force in task_forces {
+					if let Some(team_repos) = repos.task_forces.get(task_force) {
+						add_repos_for_team(&mut query_repos, main, team_repos)
+					}
+				}
+			}
+		}
+	}
+
+	if let Some(task_forces) = tf {
+		for task_force in task_forces {
+			if !groups
+				.iter()
+				.any(|(_, repos)| repos.task_forces.contains_key(task_force))
+			{
+				let known: Vec<&String> = groups
+					.iter()
+					.flat_map(|(_, repos)| repos.task_forces.keys())
+					.collect();
+				return Err(format!("No TF called '{}'—you may want to pass the TF option last on the command line. Known TFs for the selected WG(s) are:\n{:?}", task_force, known));
 			}
 		}
 	}
 
 	if query_repos.is_empty() {
-		panic!("No repos selected")
+		return Err(String::from("No repos selected"));
 	}
 
+	let mut deduped: Vec<&str> = Vec::new();
+	for repo in query_repos {
+		if !deduped.contains(&repo) {
+			deduped.push(repo)
+		}
+	}
+
+	Ok(make_repo_args(deduped))
+}
+
+/// Report a problem with the repos chosen on the command line, and stop
+pub(crate) fn selection_fail<T>(error: String) -> T {
+	panic!("{error}")
+}
+
+/// Turn a list of repos into `--repo` arguments for `gh`
+pub(crate) fn make_repo_args(query_repos: Vec<&str>) -> Vec<String> {
 	let mut query_repo_args: Vec<String> = Vec::new();
 	for repo in query_repos {
 		query_repo_args.push("--repo".to_string());
@@ -187,7 +544,11 @@ fn get_query_repos_args(
 	query_repo_args
 }
 
-fn add_repos_for_team<'a>(dest: &mut Vec<&'a str>, main: &bool, team_repos: &'a WgOrTfRepos) {
+pub(crate) fn add_repos_for_team<'a>(
+	dest: &mut Vec<&'a str>,
+	main: &bool,
+	team_repos: &'a WgOrTfRepos,
+) {
 	dest.push(&team_repos.main);
 	if !main {
 		// TODO: chain
@@ -199,9 +560,9 @@ fn add_repos_for_team<'a>(dest: &mut Vec<&'a str>, main: &bool, team_repos: &'a
 	}
 }
 
-fn get_due(line: &str) -> Option<NaiveDate> {
+pub(crate) fn get_due(line: &str) -> Option<NaiveDate> {
 	let re = Regex::new(r"^due  ?(\d\d? [[:alpha:]]{3} \d{4})$").unwrap();
-	let first_line = line.lines().next().unwrap();
+	let first_line = line.lines().next().unwrap_or_default();
 
 	if let Some(caps) = re.captures(first_line) {
 		let date_text = caps.get(1).unwrap().as_str();
@@ -215,9 +576,143 @@ fn get_due(line: &str) -> Option<NaiveDate> {
 mod tests {
 	use super::*;
 
+	fn group(json: &str) -> WorkingGroupInfo {
+		serde_json::from_str(json).unwrap()
+	}
+
+	#[test]
+	fn repos_shared_between_groups_are_deduped() {
+		let ag = group(
+			r#"{ "workingGroup": { "main": "w3c/wcag" }, "taskForces": { "coga": { "main": "w3c/coga" } } }"#,
+		);
+		let apa = group(
+			r#"{ "workingGroup": { "main": "w3c/apa" }, "taskForces": { "coga": { "main": "w3c/coga" } } }"#,
+		);
+		assert_eq!(
+			get_query_repos_args(&[("ag", &ag), ("apa", &apa)], &false, &true, &Some(vec![]))
+				.unwrap(),
+			vec!["--repo", "w3c/wcag", "--repo", "w3c/coga", "--repo", "w3c/apa"]
+		);
+	}
+
+	#[test]
+	fn task_force_found_in_any_group() {
+		let ag = group(r#"{ "workingGroup": { "main": "w3c/wcag" }, "taskForces": {} }"#);
+		let apa = group(
+			r#"{ "workingGroup": { "main": "w3c/apa" }, "taskForces": { "rq": { "main": "w3c/rqtf" } } }"#,
+		);
+		assert_eq!(
+			get_query_repos_args(
+				&[("ag", &ag), ("apa", &apa)],
+				&false,
+				&false,
+				&Some(vec![String::from("rq")])
+			)
+			.unwrap(),
+			vec!["--repo", "w3c/rqtf"]
+		);
+	}
+
+	#[test]
+	fn group_column() {
+		let ag = group(
+			r#"{ "workingGroup": { "main": "w3c/wcag" }, "taskForces": { "coga": { "main": "w3c/coga" } } }"#,
+		);
+		let apa = group(
+			r#"{ "workingGroup": { "main": "w3c/apa" }, "taskForces": { "coga": { "main": "w3c/coga" } } }"#,
+		);
+		let groups = [("ag", &ag), ("apa", &apa)];
+		assert_eq!(groups_for_repo(&groups, "w3c/coga"), String::from("ag,apa"));
+		assert_eq!(groups_for_repo(&groups, "w3c/apa"), String::from("apa"));
+	}
+
+	fn dated(repo: &str, assignees: &[&str], state: &str, due: Option<NaiveDate>) -> DatedAction {
+		let assignees: Vec<String> = assignees
+			.iter()
+			.map(|login| {
+				format!(
+					r#"{{ "id": "", "is_bot": false, "login": "{login}", "type": "", "url": "" }}"#
+				)
+			})
+			.collect();
+		let action = serde_json::from_str(&format!(
+			r#"{{
+				"assignees": [{}],
+				"author": null,
+				"body": "",
+				"createdAt": "",
+				"labels": [],
+				"number": 1,
+				"repository": {{ "name": "", "nameWithOwner": "{repo}" }},
+				"state": "{state}",
+				"title": "",
+				"updatedAt": ""
+			}}"#,
+			assignees.join(",")
+		))
+		.unwrap();
+		DatedAction {
+			action,
+			due,
+			group: None,
+		}
+	}
+
+	#[test]
+	fn sections_by_tf_and_assignee() {
+		let apa = group(
+			r#"{ "workingGroup": { "main": "w3c/apa" }, "taskForces": { "rq": { "main": "w3c/rqtf" } } }"#,
+		);
+		let groups = [("apa", &apa)];
+		let actions = vec![
+			dated("w3c/rqtf", &["a", "b"], "open", None),
+			dated("w3c/apa", &[], "open", None),
+			dated("w3c/rqtf", &["b"], "open", None),
+			dated("w3c/apa", &["c", "d", "c"], "open", None),
+		];
+
+		let by_tf = sections(actions.clone(), GroupBy::Tf, &groups);
+		assert_eq!(by_tf.keys().collect::<Vec<_>>(), vec!["WG", "rq TF"]);
+		assert_eq!(by_tf["rq TF"].len(), 2);
+
+		let by_assignee = sections(actions, GroupBy::Assignee, &groups);
+		assert_eq!(
+			by_assignee.keys().collect::<Vec<_>>(),
+			vec!["UNASSIGNED", "a", "b", "c", "d"]
+		);
+		assert_eq!(by_assignee["b"].len(), 2);
+		assert_eq!(by_assignee["c"].len(), 1);
+	}
+
+	#[test]
+	fn subtotals() {
+		let date = |day| NaiveDate::from_ymd_opt(2023, 5, day);
+		let actions = vec![
+			dated("w3c/apa", &[], "open", date(1)),
+			dated("w3c/apa", &[], "open", date(30)),
+			dated("w3c/apa", &[], "open", None),
+			dated("w3c/apa", &[], "closed", date(1)),
+		];
+		let subtotals = Subtotals::of(&actions, date(23).unwrap());
+		assert_eq!(
+			subtotals,
+			Subtotals {
+				open: 3,
+				overdue: 1,
+				undated: 1,
+				closed: 1
+			}
+		);
+		assert_eq!(
+			subtotals.to_string(),
+			"3 open (1 overdue, 1 undated), 1 closed"
+		);
+	}
+
 	#[test]
 	fn no_crash_if_no_dates() {
 		assert_eq!(get_due("Invalid request"), None);
+		assert_eq!(get_due(""), None);
 	}
 
 	#[test]
@@ -7,19 +7,44 @@
 //!
 //! For info on how to use the tool based on this library, refer to [the Nu Tracker README on GitHub](https://github.com/matatk/nu-tracker/blob/main/README.md).
 pub mod config;
+pub use calendar::calendar;
+pub use charts::{chart, Chart};
 pub use comments::comments;
-pub use issues_actions::{actions, issues};
-pub use locator::Locator;
+pub use digest::{digest, DigestFormat};
+pub use filters::Filters;
+pub use hygiene::hygiene;
+pub use issues_actions::{actions, issues, GroupBy};
+pub use locator::{ItemKind, Locator, Page};
+pub use mine::mine;
+pub use minutes::{file_actions, find_meeting_date, import_minutes, MinutedAction, SummaryStyle};
+pub use remind::remind;
+pub use serve::{serve, Backend};
+pub use show::show;
 pub use specs::specs;
+pub use stats::stats;
+pub use table_row::{SortBy, TableOptions};
 
+mod calendar;
+mod charts;
 mod comments;
+mod digest;
+mod filters;
 mod flatten_assignees;
+mod gh;
+mod hygiene;
 mod issues_actions;
 mod locator;
 mod make_table;
+mod mine;
+mod minutes;
+mod remind;
 mod returned_issue;
+mod serve;
+mod show;
 mod showing;
 mod specs;
+mod stats;
 mod status;
+mod table_row;
 
 pub use status::{FlagLabelMap, LabelStringList, ParseFlagError, Status};
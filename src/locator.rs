@@ -1,13 +1,24 @@
 use std::{fmt, str::FromStr};
 
-/// Stores info required to locate a repo
+use regex::Regex;
+
+/// Whether a locator refers to an issue or a pull request
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ItemKind {
+	Issue,
+	PullRequest,
+}
+
+/// Stores info required to locate an issue or PR in a repo
 ///
-/// This will usually be constructed via [`Locator::from_str`].
+/// This will usually be constructed via [`Locator::from_str`], or [`Locator::resolve`] for
+/// shorthand forms that need a default repo.
 #[derive(Debug, PartialEq)]
 pub struct Locator {
 	owner: String,
 	repo: String,
 	issue: u32,
+	kind: ItemKind,
 }
 
 #[derive(Debug, PartialEq)]
@@ -22,55 +33,201 @@ impl fmt::Display for Locator {
 impl FromStr for Locator {
 	type Err = LocatorError;
 
-	/// Create a Locator from a concise locator string, e.g. "w3c/apa#42"
+	/// Create a Locator from a concise locator string, e.g. "w3c/apa#42", or a GitHub URL, e.g.
+	/// "https://github.com/w3c/apa/pull/42"
 	fn from_str(locator_str: &str) -> Result<Locator, LocatorError> {
-		let slash_idx = locator_str.find('/');
-		if slash_idx.is_none() {
-			return Err(LocatorError);
-		}
-		let slash_idx = slash_idx.unwrap();
+		Locator::parse(locator_str, None)
+	}
+}
 
-		let hash_idx = locator_str.find('#');
-		if hash_idx.is_none() {
-			return Err(LocatorError);
-		}
-		let hash_idx = hash_idx.unwrap();
+impl Locator {
+	/// Create a Locator from a repo's full name (e.g. "w3c/apa") and an issue number
+	pub fn from_name_with_owner(
+		name_with_owner: &str,
+		issue: u32,
+	) -> Result<Locator, LocatorError> {
+		Locator::from_str(&format!("{name_with_owner}#{issue}"))
+	}
 
-		let owner = locator_str[0..slash_idx].to_string();
-		if owner.is_empty() {
-			return Err(LocatorError);
-		}
-		let repo = locator_str[slash_idx + 1..hash_idx].to_string();
-		if repo.is_empty() {
-			return Err(LocatorError);
-		}
+	/// Create a Locator, also accepting shorthand forms that are resolved against a default repo
+	/// (e.g. a WG's main repo, "w3c/apa")
+	///
+	/// As well as the forms accepted by [`Locator::from_str`], this accepts "apa#42" (a repo
+	/// owned by the default repo's owner, as GHURLBot does) and "#42" (the default repo itself).
+	pub fn resolve(locator_str: &str, default_repo: &str) -> Result<Locator, LocatorError> {
+		Locator::parse(locator_str, Some(default_repo))
+	}
 
-		let issue = locator_str[hash_idx + 1..].parse();
-		if issue.is_err() {
-			return Err(LocatorError);
-		}
-		let issue = issue.unwrap();
+	fn parse(locator_str: &str, default_repo: Option<&str>) -> Result<Locator, LocatorError> {
+		let url_re = Regex::new(
+			r"^(?:https?://)?github\.com/([^/#\s]+)/([^/#\s]+)/(issues|pull)/(\d+)(?:[/?#]\S*)?$",
+		)
+		.unwrap();
+		let full_re = Regex::new(r"^([^/#\s]+)/([^/#\s]+)#(\d+)$").unwrap();
+		let repo_re = Regex::new(r"^([^/#\s]+)#(\d+)$").unwrap();
+		let number_re = Regex::new(r"^#(\d+)$").unwrap();
+
+		let locator_str = locator_str.trim();
+		let default_owner_and_repo = default_repo.and_then(|name| name.split_once('/'));
 
-		if issue == 0 {
+		let (owner, repo, number, kind) = if let Some(caps) = url_re.captures(locator_str) {
+			let kind = if &caps[3] == "pull" {
+				ItemKind::PullRequest
+			} else {
+				ItemKind::Issue
+			};
+			(
+				caps[1].to_string(),
+				caps[2].to_string(),
+				caps[4].to_string(),
+				kind,
+			)
+		} else if let Some(caps) = full_re.captures(locator_str) {
+			(
+				caps[1].to_string(),
+				caps[2].to_string(),
+				caps[3].to_string(),
+				ItemKind::Issue,
+			)
+		} else if let (Some(caps), Some((owner, _))) =
+			(repo_re.captures(locator_str), default_owner_and_repo)
+		{
+			(
+				owner.to_string(),
+				caps[1].to_string(),
+				caps[2].to_string(),
+				ItemKind::Issue,
+			)
+		} else if let (Some(caps), Some((owner, repo))) =
+			(number_re.captures(locator_str), default_owner_and_repo)
+		{
+			(
+				owner.to_string(),
+				repo.to_string(),
+				caps[1].to_string(),
+				ItemKind::Issue,
+			)
+		} else {
 			return Err(LocatorError);
+		};
+
+		match number.parse() {
+			Ok(0) | Err(_) => Err(LocatorError),
+			Ok(issue) => Ok(Locator {
+				owner,
+				repo,
+				issue,
+				kind,
+			}),
 		}
+	}
 
-		Ok(Locator { owner, repo, issue })
+	/// The repo's full name, e.g. "w3c/apa"
+	pub fn name_with_owner(&self) -> String {
+		format!("{}/{}", self.owner, self.repo)
 	}
-}
 
-impl Locator {
-	/// Return the full HTTPS URL for the issue's page on GitHub.
+	/// The issue or PR number
+	pub fn number(&self) -> u32 {
+		self.issue
+	}
+
+	/// Whether this is an issue or a PR
 	///
-	/// **Note:** If this is actually a PR, GitHub will redirect the request.
+	/// Only URLs say which it is; other forms are assumed to be issues.
+	pub fn kind(&self) -> ItemKind {
+		self.kind
+	}
+
+	/// Return the full HTTPS URL for the issue's or PR's page on GitHub.
+	///
+	/// **Note:** If a PR was given in a form that's assumed to be an issue, GitHub will redirect
+	/// the request.
 	pub fn url(&self) -> String {
+		let path = match self.kind {
+			ItemKind::Issue => "issues",
+			ItemKind::PullRequest => "pull",
+		};
 		format!(
-			"https://github.com/{}/{}/issues/{}",
+			"https://github.com/{}/{}/{path}/{}",
 			self.owner, self.repo, self.issue
 		)
 	}
 }
 
+/// A page on GitHub: an issue or PR, a repo, or a repo's issues with a given label
+#[derive(Debug, PartialEq)]
+pub enum Page {
+	Item(Locator),
+	Repo(String),
+	Label { repo: String, label: String },
+}
+
+impl Page {
+	/// Work out which page is meant, resolving shorthand against a default repo (as with
+	/// [`Locator::resolve`])
+	///
+	/// If a label is given, the target must be a repo (e.g. "w3c/apa", or "apa", which is owned by
+	/// the default repo's owner), and that repo's page for the label is meant. Otherwise, the
+	/// target may be an issue or PR locator, or a repo.
+	pub fn resolve(
+		target: &str,
+		label: Option<&str>,
+		default_repo: &str,
+	) -> Result<Page, LocatorError> {
+		if label.is_none() {
+			if let Ok(locator) = Locator::resolve(target, default_repo) {
+				return Ok(Page::Item(locator));
+			}
+		}
+
+		let full_re = Regex::new(r"^[^/#\s]+/[^/#\s]+$").unwrap();
+		let repo_re = Regex::new(r"^[^/#\s]+$").unwrap();
+
+		let target = target.trim();
+		let repo = if full_re.is_match(target) {
+			target.to_string()
+		} else if let (true, Some((owner, _))) =
+			(repo_re.is_match(target), default_repo.split_once('/'))
+		{
+			format!("{owner}/{target}")
+		} else {
+			return Err(LocatorError);
+		};
+
+		Ok(match label {
+			Some(label) => Page::Label {
+				repo,
+				label: label.to_string(),
+			},
+			None => Page::Repo(repo),
+		})
+	}
+
+	/// Return the full HTTPS URL for the page on GitHub
+	pub fn url(&self) -> String {
+		match self {
+			Page::Item(locator) => locator.url(),
+			Page::Repo(repo) => format!("https://github.com/{repo}"),
+			Page::Label { repo, label } => {
+				format!("https://github.com/{repo}/labels/{}", encode(label))
+			}
+		}
+	}
+}
+
+/// Percent-encode everything other than unreserved characters (as labels may contain spaces etc.)
+fn encode(text: &str) -> String {
+	text.bytes()
+		.map(|byte| match byte {
+			b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+				(byte as char).to_string()
+			}
+			_ => format!("%{byte:02X}"),
+		})
+		.collect()
+}
+
 #[cfg(test)]
 mod tests {
 	use std::assert_eq;
@@ -86,6 +243,7 @@ mod tests {
 				owner: String::from("matatk"),
 				repo: String::from("landmarks"),
 				issue: 1,
+				kind: ItemKind::Issue,
 			}
 		)
 	}
@@ -126,6 +284,12 @@ mod tests {
 		assert_eq!(Err(LocatorError), result)
 	}
 
+	#[test]
+	fn from_name_with_owner() {
+		let result = Locator::from_name_with_owner("w3c/apa", 42).unwrap();
+		assert_eq!(result, Locator::from_str("w3c/apa#42").unwrap())
+	}
+
 	#[test]
 	fn url() {
 		let result = Locator::from_str("matatk/landmarks#1").unwrap().url();
@@ -134,4 +298,76 @@ mod tests {
 			String::from("https://github.com/matatk/landmarks/issues/1")
 		)
 	}
+
+	#[test]
+	fn urls() {
+		let issue = Locator::from_str("https://github.com/w3c/apa/issues/42").unwrap();
+		assert_eq!(issue, Locator::from_str("w3c/apa#42").unwrap());
+		assert_eq!(issue.kind(), ItemKind::Issue);
+
+		let pr = Locator::from_str("https://github.com/whatwg/html/pull/8352/files").unwrap();
+		assert_eq!(pr.to_string(), "whatwg/html#8352");
+		assert_eq!(pr.kind(), ItemKind::PullRequest);
+		assert_eq!(pr.url(), "https://github.com/whatwg/html/pull/8352");
+
+		let comment = Locator::from_str("github.com/w3c/apa/issues/42#issuecomment-1").unwrap();
+		assert_eq!(comment, issue);
+
+		assert_eq!(
+			Err(LocatorError),
+			Locator::from_str("https://github.com/w3c/apa/discussions/42")
+		);
+		assert_eq!(
+			Err(LocatorError),
+			Locator::from_str("https://github.com/w3c/apa/issues/0")
+		);
+	}
+
+	#[test]
+	fn shorthand() {
+		assert_eq!(
+			Locator::resolve("aria#7", "w3c/apa").unwrap(),
+			Locator::from_str("w3c/aria#7").unwrap()
+		);
+		assert_eq!(
+			Locator::resolve("#7", "w3c/apa").unwrap(),
+			Locator::from_str("w3c/apa#7").unwrap()
+		);
+		assert_eq!(
+			Locator::resolve("matatk/landmarks#1", "w3c/apa").unwrap(),
+			Locator::from_str("matatk/landmarks#1").unwrap()
+		);
+		assert_eq!(Err(LocatorError), Locator::from_str("aria#7"));
+		assert_eq!(Err(LocatorError), Locator::from_str("#7"));
+		assert_eq!(Err(LocatorError), Locator::resolve("#", "w3c/apa"));
+	}
+
+	#[test]
+	fn pages() {
+		assert_eq!(
+			Page::resolve("#7", None, "w3c/apa").unwrap(),
+			Page::Item(Locator::from_str("w3c/apa#7").unwrap())
+		);
+		assert_eq!(
+			Page::resolve("aria", None, "w3c/apa").unwrap().url(),
+			"https://github.com/w3c/aria"
+		);
+		assert_eq!(
+			Page::resolve("matatk/landmarks", None, "w3c/apa")
+				.unwrap()
+				.url(),
+			"https://github.com/matatk/landmarks"
+		);
+		assert_eq!(
+			Page::resolve("apa", Some("needs resolution"), "w3c/apa")
+				.unwrap()
+				.url(),
+			"https://github.com/w3c/apa/labels/needs%20resolution"
+		);
+		assert_eq!(
+			Err(LocatorError),
+			Page::resolve("w3c/apa#7", Some("action"), "w3c/apa")
+		);
+		assert_eq!(Err(LocatorError), Page::resolve("a/b/c", None, "w3c/apa"));
+	}
 }
@@ -0,0 +1,207 @@
+use std::collections::BTreeSet;
+
+use chrono::NaiveDate;
+
+use crate::comments::query_comment_requests;
+use crate::config::{Repos, WorkingGroupInfo};
+use crate::filters::Filters;
+use crate::issues_actions::{add_repos_for_team, make_repo_args, query_actions_in_repos};
+use crate::specs::query_review_requests;
+use crate::status::LabelStringList;
+use crate::table_row::{check_columns, due_cell, render, SortBy, SortKey, TableOptions, TableRow};
+
+const ME: &str = "@me";
+
+struct MyItem {
+	kind: &'static str,
+	due: Option<NaiveDate>,
+	repo: String,
+	number: u32,
+	title: String,
+	updated_at: String,
+}
+
+impl TableRow for MyItem {
+	const COLUMNS: &'static [&'static str] = &["type", "due", "locator", "title", "updated"];
+
+	fn cell(&self, column: &str) -> String {
+		match column {
+			"type" => self.kind.to_string(),
+			"due" => due_cell(self.due),
+			"locator" => format!("{}#{}", self.repo, self.number),
+			"title" => self.title.to_string(),
+			"updated" => self.updated_at.chars().take(10).collect(),
+			_ => unreachable!("unknown column '{column}'"),
+		}
+	}
+
+	fn sort_key(&self, by: SortBy) -> SortKey {
+		match by {
+			SortBy::Due => SortKey::date(self.due),
+			SortBy::Repo => SortKey::Text(self.repo.to_string()),
+			// Everything is assigned to you.
+			SortBy::Assignee => SortKey::Text(String::new()),
+			SortBy::Number => SortKey::Number(self.number),
+			SortBy::Updated => SortKey::Text(self.updated_at.to_string()),
+		}
+	}
+}
+
+/// The repos to query, across all groups, each only once
+#[derive(Debug, Default, PartialEq)]
+struct MyRepos<'a> {
+	teams: BTreeSet<&'a str>,
+	specs: BTreeSet<&'a str>,
+	comments: BTreeSet<&'a str>,
+}
+
+impl<'a> MyRepos<'a> {
+	fn of(groups: impl Iterator<Item = &'a WorkingGroupInfo>) -> Self {
+		let mut repos = MyRepos::default();
+
+		for info in groups {
+			let mut group_repos: Vec<&str> = vec![];
+			add_repos_for_team(&mut group_repos, &false, &info.working_group);
+			for task_force in info.task_forces.values() {
+				add_repos_for_team(&mut group_repos, &false, task_force);
+			}
+			repos.teams.extend(group_repos);
+
+			if let Some(horizontal_review) = &info.horizontal_review {
+				repos.specs.insert(&horizontal_review.specs);
+				repos.comments.insert(&horizontal_review.comments);
+			}
+		}
+
+		repos
+	}
+}
+
+/// Query for actions, spec reviews and comment requests assigned to you across all known groups;
+/// output a custom report, sorted by due date (unless another sort order is given).
+///
+/// Repos that are shared between groups (such as TFs with more than one parent WG) are only
+/// queried once.
+pub fn mine(repositories: &Repos, table_options: &TableOptions, verbose: &bool) {
+	if let Err(message) = check_columns::<MyItem>(table_options) {
+		println!("{message}");
+		return;
+	}
+
+	let MyRepos {
+		teams: team_repos,
+		specs: spec_repos,
+		comments: comment_repos,
+	} = MyRepos::of(repositories.wgs_repos().values());
+
+	let me = Some(String::from(ME));
+	let mut items: Vec<MyItem> = vec![];
+
+	for dated in query_actions_in_repos(
+		make_repo_args(team_repos.into_iter().collect()),
+		&me,
+		&false,
+		&Filters::default(),
+		verbose,
+	) {
+		items.push(MyItem {
+			kind: "action",
+			due: dated.due,
+			repo: dated.action.repository.name_with_owner,
+			number: dated.action.number,
+			title: dated.action.title,
+			updated_at: dated.action.updated_at,
+		})
+	}
+
+	for spec_repo in spec_repos {
+		for request in query_review_requests(spec_repo, &me, verbose) {
+			items.push(MyItem {
+				kind: "review",
+				due: Some(request.due),
+				repo: spec_repo.to_string(),
+				number: request.number,
+				title: request.spec,
+				updated_at: request.updated_at,
+			})
+		}
+	}
+
+	let no_status = LabelStringList::default();
+	for comment_repo in comment_repos {
+		for request in query_comment_requests(comment_repo, &no_status, &me, None, verbose) {
+			items.push(MyItem {
+				kind: "comment",
+				due: None,
+				repo: comment_repo.to_string(),
+				number: request.tracking_number,
+				title: request.title,
+				updated_at: request.updated_at,
+			})
+		}
+	}
+
+	if items.is_empty() {
+		println!("Nothing assigned to you in any known group's repos");
+		return;
+	} else {
+		println!("{} items assigned to you\n", items.len())
+	}
+
+	// Undated items (such as comment requests) go last.
+	items.sort_by_key(|item| (item.due.is_none(), item.due));
+
+	match render(
+		items,
+		&["type", "due", "locator", "title"],
+		table_options,
+		&[],
+	) {
+		Ok(table) => println!("{table}"),
+		Err(message) => println!("{message}"),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn group(json: serde_json::Value) -> WorkingGroupInfo {
+		serde_json::from_value(json).unwrap()
+	}
+
+	#[test]
+	fn shared_repos_queried_once() {
+		let apa = group(serde_json::json!({
+			"horizontalReview": { "specs": "w3c/a11y-request", "comments": "w3c/a11y-review" },
+			"workingGroup": { "main": "w3c/apa", "others": ["w3c/media-accessibility-reqs"] },
+			"taskForces": {
+				"aria": { "main": "w3c/aria" },
+				"maturity": { "main": "w3c/maturity-model" },
+			},
+		}));
+		let ag = group(serde_json::json!({
+			"horizontalReview": { "specs": "w3c/a11y-request", "comments": "w3c/a11y-review" },
+			"workingGroup": { "main": "w3c/wcag" },
+			"taskForces": {
+				"maturity": { "main": "w3c/maturity-model" },
+			},
+		}));
+
+		let repos = MyRepos::of([&apa, &ag].into_iter());
+		assert_eq!(
+			repos,
+			MyRepos {
+				teams: BTreeSet::from([
+					"w3c/apa",
+					"w3c/aria",
+					"w3c/maturity-model",
+					"w3c/media-accessibility-reqs",
+					"w3c/wcag",
+				]),
+				specs: BTreeSet::from(["w3c/a11y-request"]),
+				comments: BTreeSet::from(["w3c/a11y-review"]),
+			}
+		);
+	}
+}
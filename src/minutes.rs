@@ -0,0 +1,671 @@
+use std::io::{self, Write};
+use std::{collections::BTreeMap, process::Command, str};
+
+use chrono::{Days, Months, NaiveDate};
+use regex::Regex;
+
+use crate::config::WorkingGroupInfo;
+use crate::filters::Filters;
+use crate::issues_actions::{query_actions, DatedAction};
+use crate::make_table::make_table;
+
+/// How to list actions for a meeting
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SummaryStyle {
+	/// The "Summary of action items" block in W3C minutes
+	Minutes,
+	/// One line per action, to paste into IRC
+	Irc,
+}
+
+/// An action recorded in meeting minutes
+pub struct MinutedAction {
+	pub assignees: Vec<String>,
+	pub description: String,
+	/// As written in the minutes (e.g. "next week")
+	pub due_text: Option<String>,
+	pub due: Option<NaiveDate>,
+	/// The existing action issue that this matches, if any
+	pub filed_as: Option<String>,
+}
+
+impl MinutedAction {
+	/// An issue body that records the due date as [`crate::issues_actions::get_due`] expects,
+	/// and names any assignees that couldn't be assigned on GitHub
+	fn body(&self, source: &str, unassigned: &[&str]) -> String {
+		let mut body = String::new();
+		if let Some(due) = self.due {
+			body.push_str(&format!("due {}\n\n", due.format("%-d %b %Y")))
+		}
+		if !unassigned.is_empty() {
+			body.push_str(&format!(
+				"Assigned in the minutes to: {}\n\n",
+				unassigned.join(", ")
+			))
+		}
+		body.push_str(&format!("Recorded in the minutes: {source}"));
+		body
+	}
+}
+
+/// Find the actions recorded in meeting minutes (scribe.perl HTML output, or an IRC log), check
+/// them against the group's existing actions, and report on them. Those that were never filed are
+/// returned.
+///
+/// Relative due dates (e.g. "next week", "in 2 weeks") are worked out from the meeting date.
+pub fn import_minutes(
+	group_name: &str,
+	repos: &WorkingGroupInfo,
+	minutes: &str,
+	is_html: bool,
+	meeting_date: NaiveDate,
+	verbose: &bool,
+) -> Vec<MinutedAction> {
+	let text = if is_html {
+		html_to_text(minutes)
+	} else {
+		minutes.to_string()
+	};
+	let mut actions = parse_minutes(&text, meeting_date);
+
+	if actions.is_empty() {
+		println!("No actions found in the minutes");
+		return vec![];
+	}
+
+	// GHURLBot files actions during the meeting, so only those created since then can match.
+	let existing = query_actions(
+		&[(group_name, repos)],
+		&None,
+		&true,
+		&false,
+		&true,
+		&Some(vec![]),
+		&Filters {
+			created_since: Some(meeting_date),
+			..Default::default()
+		},
+		verbose,
+	);
+	for action in &mut actions {
+		action.filed_as = find_filed(action, &existing)
+	}
+
+	let rows: Vec<Vec<String>> = actions
+		.iter()
+		.map(|action| {
+			vec![
+				action.filed_as.clone().unwrap_or(String::from("NOT FILED")),
+				match (action.due, &action.due_text) {
+					(Some(due), _) => due.to_string(),
+					(None, Some(text)) => format!("? ({text})"),
+					(None, None) => String::from("(no date)"),
+				},
+				action.assignees.join(", "),
+				action.description.clone(),
+			]
+		})
+		.collect();
+
+	let not_filed: Vec<MinutedAction> = actions
+		.into_iter()
+		.filter(|action| action.filed_as.is_none())
+		.collect();
+
+	println!(
+		"{} actions in the minutes; {} not filed\n",
+		rows.len(),
+		not_filed.len()
+	);
+	println!(
+		"{}",
+		make_table(vec!["FILED", "DUE", "ASSIGNEES", "ACTION"], rows, None)
+	);
+
+	not_filed
+}
+
+/// File the given actions as issues with the "action" label in the given repo
+///
+/// Assignees from the minutes are IRC nicks, which aren't always GitHub logins, so they're all
+/// checked first; those that can't be assigned in the repo are named in the issue body instead. If
+/// any actions can't be filed, the rest still are, and an error listing them is returned.
+pub fn file_actions(
+	repo: &str,
+	actions: &[MinutedAction],
+	source: &str,
+	verbose: &bool,
+) -> Result<(), String> {
+	let mut assignable: BTreeMap<&str, bool> = BTreeMap::new();
+	for name in actions.iter().flat_map(|action| &action.assignees) {
+		if !assignable.contains_key(name.as_str()) {
+			assignable.insert(name, is_assignable(repo, name, verbose)?);
+		}
+	}
+	let unknown: Vec<&str> = assignable
+		.iter()
+		.filter(|(_, can_assign)| !**can_assign)
+		.map(|(name, _)| *name)
+		.collect();
+	if !unknown.is_empty() {
+		println!(
+			"NOTE: can't assign {} in {repo}, so they'll be named in the issues instead",
+			unknown.join(", ")
+		)
+	}
+
+	let mut failed: Vec<&str> = vec![];
+	for action in actions {
+		let (assignees, unassigned): (Vec<&str>, Vec<&str>) = action
+			.assignees
+			.iter()
+			.map(String::as_str)
+			.partition(|name| assignable[name]);
+
+		let mut cmd = Command::new("gh");
+		cmd.args(["issue", "create"])
+			.args(["--repo", repo])
+			.args(["--title", &action.description])
+			.args(["--label", "action"])
+			.args(["--body", &action.body(source, &unassigned)]);
+		for assignee in assignees {
+			cmd.args(["--assignee", assignee]);
+		}
+
+		if *verbose {
+			println!("Minutes: running: {cmd:?}");
+		}
+		let output = cmd
+			.output()
+			.map_err(|error| format!("Couldn't run 'gh': {error}"))?;
+
+		if output.status.success() {
+			io::stdout().write_all(&output.stdout).unwrap();
+		} else {
+			io::stdout().write_all(&output.stdout).unwrap();
+			io::stderr().write_all(&output.stderr).unwrap();
+			failed.push(&action.description)
+		}
+	}
+
+	if failed.is_empty() {
+		Ok(())
+	} else {
+		Err(format!(
+			"Couldn't file {} of {} actions: {}",
+			failed.len(),
+			actions.len(),
+			failed.join("; ")
+		))
+	}
+}
+
+/// Whether the user can be assigned issues in the repo
+fn is_assignable(repo: &str, login: &str, verbose: &bool) -> Result<bool, String> {
+	let mut cmd = Command::new("gh");
+	cmd.args(["api", "--silent"])
+		.arg(format!("repos/{repo}/assignees/{login}"));
+
+	if *verbose {
+		println!("Minutes: running: {cmd:?}");
+	}
+	// GitHub responds with 'not found' for users that can't be assigned.
+	cmd.output()
+		.map(|output| output.status.success())
+		.map_err(|error| format!("Couldn't run 'gh': {error}"))
+}
+
+/// Find the meeting date in scribe.perl minutes: from the title (e.g. "APA Weekly -- 10 January
+/// 2024"), or the minutes' URL (e.g. "https://www.w3.org/2024/01/10-apa-minutes.html")
+pub fn find_meeting_date(minutes: &str) -> Option<NaiveDate> {
+	let title_re = Regex::new(r"--\s*(\d{1,2} [A-Za-z]+ \d{4})\b").unwrap();
+	let from_title = title_re.captures(minutes).and_then(|caps| {
+		["%d %B %Y", "%d %b %Y"]
+			.iter()
+			.find_map(|format| NaiveDate::parse_from_str(&caps[1], format).ok())
+	});
+
+	let url_re = Regex::new(r"/(\d{4})/(\d{2})/(\d{2})-[\w-]+-(?:minutes|irc)\b").unwrap();
+	from_title.or_else(|| {
+		let caps = url_re.captures(minutes)?;
+		NaiveDate::from_ymd_opt(
+			caps[1].parse().ok()?,
+			caps[2].parse().ok()?,
+			caps[3].parse().ok()?,
+		)
+	})
+}
+
+/// List actions for a meeting, marking those created on or after `new_since` as new
+pub(crate) fn minutes_summary(
+	actions: &[DatedAction],
+	style: SummaryStyle,
+	new_since: NaiveDate,
+) -> String {
+	let mut out = String::new();
+	if style == SummaryStyle::Minutes {
+		out.push_str("Summary of action items\n\n")
+	}
+
+	for dated in actions {
+		let issue = &dated.action;
+		let locator = format!("{}#{}", issue.repository.name_with_owner, issue.number);
+		let names: Vec<&str> = issue
+			.assignees
+			.iter()
+			.map(|assignee| assignee.login.as_str())
+			.collect();
+		let is_new = issue
+			.created_at
+			.get(..10)
+			.and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+			.is_some_and(|created| created >= new_since);
+
+		let line = match style {
+			SummaryStyle::Minutes => {
+				let who = if names.is_empty() {
+					String::new()
+				} else {
+					format!("{} to ", names.join(", "))
+				};
+				let due = dated
+					.due
+					.map(|due| format!(" - due {due}"))
+					.unwrap_or_default();
+				format!(
+					"{}ACTION: {who}{} [{locator}]{due}",
+					if is_new { "[NEW] " } else { "" },
+					issue.title
+				)
+			}
+			SummaryStyle::Irc => {
+				let mut details = names.clone();
+				let due = dated.due.map(|due| format!("due {due}"));
+				if let Some(due) = &due {
+					details.push(due)
+				}
+				if is_new {
+					details.push("new")
+				}
+				let details = if details.is_empty() {
+					String::new()
+				} else {
+					format!(" ({})", details.join(", "))
+				};
+				format!("{locator}{details}: {}", issue.title)
+			}
+		};
+		out.push_str(&line);
+		out.push('\n')
+	}
+
+	out
+}
+
+fn html_to_text(html: &str) -> String {
+	let breaks = Regex::new(r"(?i)<br\s*/?>|</(p|li|div|dd|dt|h\d)>").unwrap();
+	let tags = Regex::new(r"<[^>]*>").unwrap();
+
+	let text = breaks.replace_all(html, "\n");
+	tags.replace_all(&text, "")
+		.replace("&lt;", "<")
+		.replace("&gt;", ">")
+		.replace("&quot;", "\"")
+		.replace("&#39;", "'")
+		.replace("&nbsp;", " ")
+		.replace("&amp;", "&")
+}
+
+fn parse_minutes(text: &str, meeting_date: NaiveDate) -> Vec<MinutedAction> {
+	// Lines may start with a timestamp, a speaker (as "<nick>" or "nick:") and a status tag.
+	let line_re = Regex::new(
+		r"(?i)^\s*(?:\[?\d{1,2}:\d{2}(?::\d{2})?\]?\s*)?(?:<[^>\s]+>\s*|[\w.-]+:\s+)?(?:\[\w+\]\s*)?action:\s*(.+)$",
+	)
+	.unwrap();
+	let recorded_re = Regex::new(r"\s*\[recorded in [^\]]*\]\s*$").unwrap();
+	let due_re = Regex::new(r"(?i)\s+-\s+due\s+(.+)$").unwrap();
+	let assignees_re =
+		Regex::new(r"(?i)^(@?[\w-]+(?:\s*(?:,|and)\s*@?[\w-]+)*)\s+to\s+(.+)$").unwrap();
+	let separator_re = Regex::new(r"(?i)\s*(?:,|\band\b)\s*").unwrap();
+
+	let mut actions: Vec<MinutedAction> = vec![];
+	for line in text.lines() {
+		let Some(caps) = line_re.captures(line) else {
+			continue;
+		};
+		let content = recorded_re.replace(caps[1].trim(), "").to_string();
+
+		let (content, due_text) = match due_re.captures(&content) {
+			Some(caps) => (
+				due_re.replace(&content, "").to_string(),
+				Some(caps[1].trim().to_string()),
+			),
+			None => (content.clone(), None),
+		};
+
+		let (assignees, description) = match assignees_re.captures(&content) {
+			Some(caps) => (
+				separator_re
+					.split(&caps[1])
+					.map(|name| name.trim_start_matches('@').to_string())
+					.filter(|name| !name.is_empty())
+					.collect(),
+				caps[2].trim().to_string(),
+			),
+			None => (vec![], content.trim().to_string()),
+		};
+
+		// scribe.perl lists the actions again in its summary.
+		if description.is_empty()
+			|| actions
+				.iter()
+				.any(|action| normalise(&action.description) == normalise(&description))
+		{
+			continue;
+		}
+
+		actions.push(MinutedAction {
+			assignees,
+			description,
+			due: due_text
+				.as_deref()
+				.and_then(|text| parse_due(text, meeting_date)),
+			due_text,
+			filed_as: None,
+		})
+	}
+
+	actions
+}
+
+/// Work out a due date from how it's given in the minutes
+fn parse_due(text: &str, meeting_date: NaiveDate) -> Option<NaiveDate> {
+	let text = text.trim().trim_end_matches('.').to_lowercase();
+
+	for format in ["%Y-%m-%d", "%d %b %Y", "%d %B %Y"] {
+		if let Ok(date) = NaiveDate::parse_from_str(&text, format) {
+			return Some(date);
+		}
+	}
+
+	let relative_re =
+		Regex::new(r"^(?:in\s+)?(\d+|a|one|two|three|four)\s+(day|week|month)s?$").unwrap();
+	let (count, unit) = match text.as_str() {
+		"tomorrow" => (1, "day"),
+		"next week" => (1, "week"),
+		"next month" => (1, "month"),
+		_ => {
+			let caps = relative_re.captures(&text)?;
+			let count = match &caps[1] {
+				"a" | "one" => 1,
+				"two" => 2,
+				"three" => 3,
+				"four" => 4,
+				number => number.parse().ok()?,
+			};
+			let unit = match &caps[2] {
+				"day" => "day",
+				"week" => "week",
+				_ => "month",
+			};
+			(count, unit)
+		}
+	};
+
+	match unit {
+		"day" => meeting_date.checked_add_days(Days::new(count)),
+		"week" => meeting_date.checked_add_days(Days::new(7 * count)),
+		_ => meeting_date.checked_add_months(Months::new(count as u32)),
+	}
+}
+
+/// Lower-case words, without punctuation, for comparing titles
+fn normalise(text: &str) -> String {
+	text.to_lowercase()
+		.split(|character: char| !character.is_alphanumeric())
+		.filter(|word| !word.is_empty())
+		.collect::<Vec<&str>>()
+		.join(" ")
+}
+
+/// Whether the (normalised) `words` appear, as whole words, in the (normalised) `text`
+fn contains_words(text: &str, words: &str) -> bool {
+	format!(" {text} ").contains(&format!(" {words} "))
+}
+
+/// Find an existing action with a matching title (and assignee, if the minutes give one)
+///
+/// The title and description match if either one's words appear, in order, in the other.
+fn find_filed(action: &MinutedAction, existing: &[DatedAction]) -> Option<String> {
+	let description = normalise(&action.description);
+	if description.is_empty() {
+		return None;
+	}
+	existing
+		.iter()
+		.find(|dated| {
+			let title = normalise(&dated.action.title);
+			let same_text =
+				contains_words(&title, &description) || contains_words(&description, &title);
+			let same_assignee = action.assignees.is_empty()
+				|| dated.action.assignees.iter().any(|assignee| {
+					action
+						.assignees
+						.iter()
+						.any(|name| name.eq_ignore_ascii_case(&assignee.login))
+				});
+			!title.is_empty() && same_text && same_assignee
+		})
+		.map(|dated| {
+			format!(
+				"{}#{}",
+				dated.action.repository.name_with_owner, dated.action.number
+			)
+		})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::issues_actions::get_due;
+
+	fn date(text: &str) -> NaiveDate {
+		NaiveDate::parse_from_str(text, "%Y-%m-%d").unwrap()
+	}
+
+	#[test]
+	fn irc_log() {
+		let log = "\
+[14:02] <alice> topic: Reviews
+[14:05] <bob> action: @alice to review the CSS spec - due next week
+[14:06] <chair> ACTION: bob, carol to draft a reply - due 2024-02-01
+14:07 scribe: ACTION: Update the wiki
+[14:08] <bob> we need more action items
+[14:09] <chair> action: @dave to find a date - due some time";
+
+		let actions = parse_minutes(log, date("2024-01-10"));
+		assert_eq!(actions.len(), 4);
+
+		assert_eq!(actions[0].assignees, vec!["alice"]);
+		assert_eq!(actions[0].description, "review the CSS spec");
+		assert_eq!(actions[0].due, Some(date("2024-01-17")));
+
+		assert_eq!(actions[1].assignees, vec!["bob", "carol"]);
+		assert_eq!(actions[1].description, "draft a reply");
+		assert_eq!(actions[1].due, Some(date("2024-02-01")));
+
+		assert!(actions[2].assignees.is_empty());
+		assert_eq!(actions[2].description, "Update the wiki");
+		assert_eq!(actions[2].due_text, None);
+
+		assert_eq!(actions[3].due_text, Some(String::from("some time")));
+		assert_eq!(actions[3].due, None);
+	}
+
+	#[test]
+	fn scribe_html() {
+		let html = "<p class='phone'>&lt;alice&gt; ACTION: Bob to check links &amp; fix them - due 2 weeks</p>
+<h2>Summary of action items</h2>
+<div><strong>[NEW]</strong> <strong>ACTION:</strong> Bob to check links &amp; fix them - due 2 weeks [recorded in <a href='#a01'>https://www.w3.org/2024/01/10-apa-minutes.html#a01</a>]<br></div>";
+
+		let actions = parse_minutes(&html_to_text(html), date("2024-01-10"));
+		assert_eq!(actions.len(), 1);
+		assert_eq!(actions[0].assignees, vec!["Bob"]);
+		assert_eq!(actions[0].description, "check links & fix them");
+		assert_eq!(actions[0].due, Some(date("2024-01-24")));
+	}
+
+	#[test]
+	fn due_dates() {
+		let meeting = date("2024-01-31");
+		let due = |text| parse_due(text, meeting);
+		assert_eq!(due("tomorrow"), Some(date("2024-02-01")));
+		assert_eq!(due("in 3 days"), Some(date("2024-02-03")));
+		assert_eq!(due("a week"), Some(date("2024-02-07")));
+		assert_eq!(due("next month"), Some(date("2024-02-29")));
+		assert_eq!(due("23 May 2027"), Some(date("2027-05-23")));
+		assert_eq!(due("4 June 2028."), Some(date("2028-06-04")));
+		assert_eq!(due("soon"), None);
+	}
+
+	#[test]
+	fn body_has_due_date() {
+		let action = MinutedAction {
+			assignees: vec![],
+			description: String::from("Do it"),
+			due_text: None,
+			due: Some(date("2028-06-04")),
+			filed_as: None,
+		};
+		assert_eq!(get_due(&action.body("minutes.html", &[])), action.due);
+		assert_eq!(get_due(&action.body("minutes.html", &["bob"])), action.due);
+	}
+
+	#[test]
+	fn meeting_dates() {
+		assert_eq!(
+			find_meeting_date("See: https://www.w3.org/2024/01/10-apa-minutes.html#a01"),
+			Some(date("2024-01-10"))
+		);
+		assert_eq!(
+			find_meeting_date("<title>APA Weekly Teleconference -- 7 Feb 2024</title>"),
+			Some(date("2024-02-07"))
+		);
+		assert_eq!(
+			find_meeting_date("<h2>APA -- 31 January 2024</h2>"),
+			Some(date("2024-01-31"))
+		);
+		assert_eq!(find_meeting_date("[14:02] <alice> topic: Reviews"), None);
+	}
+
+	fn returned(number: u32, assignees: &[&str], created: &str, title: &str) -> DatedAction {
+		let assignees: Vec<String> = assignees
+			.iter()
+			.map(|login| {
+				format!(
+					r#"{{ "id": "", "is_bot": false, "login": "{login}", "type": "", "url": "" }}"#
+				)
+			})
+			.collect();
+		DatedAction::from(
+			serde_json::from_str::<crate::returned_issue::ReturnedIssue>(&format!(
+				r#"{{
+					"assignees": [{}],
+					"author": null,
+					"body": "due 17 Jan 2024",
+					"createdAt": "{created}T09:00:00Z",
+					"labels": [],
+					"number": {number},
+					"repository": {{ "name": "apa", "nameWithOwner": "w3c/apa" }},
+					"state": "open",
+					"title": "{title}",
+					"updatedAt": ""
+				}}"#,
+				assignees.join(",")
+			))
+			.unwrap(),
+		)
+	}
+
+	#[test]
+	fn summaries() {
+		let mut undated = returned(2, &[], "2024-01-01", "Update the wiki");
+		undated.due = None;
+		let actions = [
+			returned(1, &["alice", "bob"], "2024-01-10", "Review the spec"),
+			undated,
+		];
+		let meeting = date("2024-01-10");
+
+		assert_eq!(
+			minutes_summary(&actions, SummaryStyle::Minutes, meeting),
+			"Summary of action items
+
+[NEW] ACTION: alice, bob to Review the spec [w3c/apa#1] - due 2024-01-17
+ACTION: Update the wiki [w3c/apa#2]
+"
+		);
+		assert_eq!(
+			minutes_summary(&actions, SummaryStyle::Irc, meeting),
+			"w3c/apa#1 (alice, bob, due 2024-01-17, new): Review the spec
+w3c/apa#2: Update the wiki
+"
+		);
+	}
+
+	#[test]
+	fn filed_actions_found() {
+		let existing = [returned(
+			42,
+			&["alice"],
+			"2024-01-10",
+			"Review the CSS spec.",
+		)];
+		let minuted = |assignee: &str, description: &str| MinutedAction {
+			assignees: vec![assignee.to_string()],
+			description: description.to_string(),
+			due_text: None,
+			due: None,
+			filed_as: None,
+		};
+
+		assert_eq!(
+			find_filed(&minuted("Alice", "review the CSS spec"), &existing),
+			Some(String::from("w3c/apa#42"))
+		);
+		assert_eq!(
+			find_filed(&minuted("bob", "review the CSS spec"), &existing),
+			None
+		);
+		assert_eq!(
+			find_filed(&minuted("alice", "write tests"), &existing),
+			None
+		);
+	}
+
+	#[test]
+	fn filed_actions_match_whole_words() {
+		let existing = [returned(
+			42,
+			&["alice"],
+			"2024-01-10",
+			"Review the CSS spec.",
+		)];
+		let minuted = |description: &str| MinutedAction {
+			assignees: vec![],
+			description: description.to_string(),
+			due_text: None,
+			due: None,
+			filed_as: None,
+		};
+
+		let found = Some(String::from("w3c/apa#42"));
+		assert_eq!(find_filed(&minuted("the CSS spec"), &existing), found);
+		assert_eq!(
+			find_filed(&minuted("Review the CSS spec, and reply"), &existing),
+			found
+		);
+		assert_eq!(find_filed(&minuted("view the C"), &existing), None);
+		assert_eq!(find_filed(&minuted("..."), &existing), None);
+	}
+}
@@ -0,0 +1,258 @@
+use std::io::{self, Write};
+use std::{collections::BTreeMap, fs, path::Path, process::Command, str};
+
+use chrono::{Days, Local, NaiveDate};
+
+use crate::config::{config_dir, WorkingGroupInfo};
+use crate::filters::Filters;
+use crate::issues_actions::query_actions;
+use crate::specs::query_review_requests;
+
+const SENT_FILE_NAME: &str = "reminders.json";
+
+/// Something that's due soon
+#[derive(Debug, PartialEq)]
+struct Upcoming {
+	repo: String,
+	number: u32,
+	title: String,
+	due: NaiveDate,
+	assignees: Vec<String>,
+}
+
+impl Upcoming {
+	fn locator(&self) -> String {
+		format!("{}#{}", self.repo, self.number)
+	}
+
+	fn message(&self, today: NaiveDate) -> String {
+		let when = match (self.due - today).num_days() {
+			0 => String::from("today"),
+			1 => String::from("tomorrow"),
+			days => format!("in {days} days"),
+		};
+		let mentions: Vec<String> = self
+			.assignees
+			.iter()
+			.map(|login| format!("@{login}"))
+			.collect();
+		let mut message = format!("Reminder: this is due on {} ({when}).", self.due);
+		if !mentions.is_empty() {
+			message = format!("{} {message}", mentions.join(" "))
+		}
+		message
+	}
+}
+
+/// Which reminders have been sent, by locator, with the due date each was for
+///
+/// If an item's due date changes, it'll be reminded about again.
+#[derive(Debug, Default, PartialEq)]
+struct Sent(BTreeMap<String, String>);
+
+impl Sent {
+	fn load(path: &Path) -> io::Result<Sent> {
+		if !path.exists() {
+			return Ok(Sent::default());
+		}
+		serde_json::from_str(&fs::read_to_string(path)?)
+			.map(Sent)
+			.map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+	}
+
+	fn save(&self, path: &Path) -> io::Result<()> {
+		fs::write(
+			path,
+			serde_json::to_string_pretty(&self.0).expect("should be able to serialise reminders"),
+		)
+	}
+
+	fn already(&self, upcoming: &Upcoming) -> bool {
+		self.0.get(&upcoming.locator()) == Some(&upcoming.due.to_string())
+	}
+
+	fn record(&mut self, upcoming: &Upcoming) {
+		self.0.insert(upcoming.locator(), upcoming.due.to_string());
+	}
+
+	/// Forget reminders for due dates that have passed
+	fn prune(&mut self, today: NaiveDate) {
+		self.0.retain(|_, due| {
+			NaiveDate::parse_from_str(due, "%Y-%m-%d").is_ok_and(|due| due >= today)
+		})
+	}
+}
+
+/// Remind people about the WG's (and all TFs') actions, and spec reviews, that are due within
+/// `days` days
+///
+/// Reminders are posted as comments that mention the assignees (if `post` is true), or printed so
+/// they can be copied and pasted. Posted reminders are recorded in the config dir (as soon as each
+/// is posted), so that nobody is reminded twice about the same due date; printed ones are only
+/// recorded if `record` is true. Nothing is posted or recorded in a dry run.
+pub fn remind(
+	group_name: &str,
+	repos: &WorkingGroupInfo,
+	days: u64,
+	post: bool,
+	record: bool,
+	dry_run: bool,
+	verbose: &bool,
+) -> io::Result<()> {
+	let today = Local::now().date_naive();
+	let until = today + Days::new(days);
+	let sent_path = config_dir().join(SENT_FILE_NAME);
+	let mut sent = Sent::load(&sent_path)?;
+	sent.prune(today);
+
+	let mut upcoming: Vec<Upcoming> = query_actions(
+		&[(group_name, repos)],
+		&None,
+		&false,
+		&false,
+		&true,
+		&Some(vec![]),
+		&Filters::everything(),
+		verbose,
+	)
+	.into_iter()
+	.filter_map(|dated| {
+		Some(Upcoming {
+			repo: dated.action.repository.name_with_owner,
+			number: dated.action.number,
+			title: dated.action.title,
+			due: dated.due?,
+			assignees: dated
+				.action
+				.assignees
+				.into_iter()
+				.map(|assignee| assignee.login)
+				.collect(),
+		})
+	})
+	.collect();
+
+	if let Some(horizontal_review) = &repos.horizontal_review {
+		upcoming.extend(
+			query_review_requests(&horizontal_review.specs, &None, verbose)
+				.into_iter()
+				.map(|request| Upcoming {
+					repo: horizontal_review.specs.to_string(),
+					number: request.number,
+					title: request.spec,
+					due: request.due,
+					assignees: request
+						.assignees
+						.into_iter()
+						.map(|assignee| assignee.login)
+						.collect(),
+				}),
+		)
+	}
+
+	upcoming.retain(|item| item.due >= today && item.due <= until);
+	upcoming.sort_by_key(|item| item.due);
+	let (done, to_send): (Vec<Upcoming>, Vec<Upcoming>) =
+		upcoming.into_iter().partition(|item| sent.already(item));
+
+	if to_send.is_empty() {
+		println!("Nothing new due in the next {days} days");
+	}
+	if !done.is_empty() {
+		println!("{} already reminded about", done.len())
+	}
+
+	for item in &to_send {
+		let message = item.message(today);
+		if post && !dry_run {
+			post_comment(item, &message, verbose)?;
+			println!("Reminded: {} ({})", item.locator(), item.title)
+		} else {
+			let prefix = if dry_run && post {
+				"Would post on "
+			} else {
+				""
+			};
+			println!("{prefix}{} ({}): {message}", item.locator(), item.title)
+		}
+
+		// Saved each time, so that if a later post fails, those already posted aren't repeated.
+		if (post || record) && !dry_run {
+			sent.record(item);
+			sent.save(&sent_path)?
+		}
+	}
+
+	Ok(())
+}
+
+fn post_comment(item: &Upcoming, message: &str, verbose: &bool) -> io::Result<()> {
+	let mut cmd = Command::new("gh");
+	cmd.args(["issue", "comment", &item.number.to_string()])
+		.args(["--repo", &item.repo])
+		.args(["--body", message]);
+
+	if *verbose {
+		println!("Remind: running: {cmd:?}");
+	}
+	let output = cmd.output()?;
+
+	if output.status.success() {
+		Ok(())
+	} else {
+		io::stdout().write_all(&output.stdout)?;
+		io::stderr().write_all(&output.stderr)?;
+		Err(io::Error::other(format!(
+			"'gh' couldn't comment on {}",
+			item.locator()
+		)))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn date(text: &str) -> NaiveDate {
+		NaiveDate::parse_from_str(text, "%Y-%m-%d").unwrap()
+	}
+
+	fn upcoming(due: &str, assignees: &[&str]) -> Upcoming {
+		Upcoming {
+			repo: String::from("w3c/apa"),
+			number: 42,
+			title: String::from("Do the thing"),
+			due: date(due),
+			assignees: assignees.iter().map(|login| login.to_string()).collect(),
+		}
+	}
+
+	#[test]
+	fn messages() {
+		let today = date("2024-01-10");
+		assert_eq!(
+			upcoming("2024-01-13", &["alice", "bob"]).message(today),
+			"@alice @bob Reminder: this is due on 2024-01-13 (in 3 days)."
+		);
+		assert_eq!(
+			upcoming("2024-01-11", &[]).message(today),
+			"Reminder: this is due on 2024-01-11 (tomorrow)."
+		);
+	}
+
+	#[test]
+	fn not_reminded_twice() {
+		let mut sent = Sent::default();
+		let item = upcoming("2024-01-13", &["alice"]);
+		assert!(!sent.already(&item));
+
+		sent.record(&item);
+		assert!(sent.already(&item));
+		assert!(!sent.already(&upcoming("2024-01-20", &["alice"])));
+
+		sent.prune(date("2024-01-13"));
+		assert!(sent.already(&item));
+		sent.prune(date("2024-01-14"));
+		assert_eq!(sent, Sent::default());
+	}
+}
@@ -3,7 +3,8 @@ use serde::{Deserialize, Serialize};
 use struct_field_names_as_array::FieldNamesAsArray;
 
 #[derive(Clone, Serialize, Deserialize, FieldNamesAsArray)]
-#[field_names_as_array(visibility = "pub(super)")]
+#[serde(rename_all = "camelCase")]
+#[field_names_as_array(visibility = "pub(super)", rename_all = "camelCase")]
 pub struct ReturnedIssueHeavy {
 	pub assignees: Vec<Assignee>,
 	pub body: String,
@@ -11,24 +12,45 @@ pub struct ReturnedIssueHeavy {
 	pub number: u32,
 	pub repository: Repository,
 	pub title: String,
+	pub updated_at: String,
 }
 
 #[derive(Clone, Serialize, Deserialize, FieldNamesAsArray)]
-#[field_names_as_array(visibility = "pub(super)")]
+#[serde(rename_all = "camelCase")]
+#[field_names_as_array(visibility = "pub(super)", rename_all = "camelCase")]
 pub struct ReturnedIssue {
 	pub assignees: Vec<Assignee>,
+	pub author: Option<Author>,
 	pub body: String,
+	pub created_at: String,
+	pub labels: Vec<Label>,
 	pub number: u32,
 	pub repository: Repository,
+	pub state: String,
 	pub title: String,
+	pub updated_at: String,
 }
 
 #[derive(Serialize, Deserialize, FieldNamesAsArray)]
-#[field_names_as_array(visibility = "pub(super)")]
+#[serde(rename_all = "camelCase")]
+#[field_names_as_array(visibility = "pub(super)", rename_all = "camelCase")]
 pub struct ReturnedIssueLight {
 	pub assignees: Vec<Assignee>,
 	pub number: u32,
 	pub title: String,
+	pub updated_at: String,
+}
+
+#[derive(Serialize, Deserialize, FieldNamesAsArray)]
+#[serde(rename_all = "camelCase")]
+#[field_names_as_array(visibility = "pub(super)", rename_all = "camelCase")]
+pub struct ReturnedDatedIssue {
+	/// Not given (or a placeholder date, before the creation date) for open issues
+	pub closed_at: Option<String>,
+	pub created_at: String,
+	pub labels: Vec<Label>,
+	pub number: u32,
+	pub title: String,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -40,6 +62,11 @@ pub struct Assignee {
 	pub url: String,
 }
 
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Author {
+	pub login: String,
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct Label {
 	pub id: String,
@@ -0,0 +1,402 @@
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::{Duration, Instant};
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use chrono::{SecondsFormat, Utc};
+
+use crate::comments::try_query_comment_requests;
+use crate::config::WorkingGroupInfo;
+use crate::issues_actions::{get_query_repos_args, try_query_actions_in_repos};
+use crate::specs::try_query_review_requests;
+use crate::status::LabelStringList;
+
+mod item;
+mod query;
+
+use item::{atom, Item};
+use query::{route, Endpoint, Query};
+
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Where the served items come from
+pub enum Backend {
+	/// Query GitHub (via `gh`), keeping the results for the given time
+	GitHub(Duration),
+	/// Read JSON files named after the endpoints (e.g. "actions.json") from a directory, for
+	/// testing dashboards locally
+	Fixtures(PathBuf),
+}
+
+struct Response {
+	status: u16,
+	content_type: &'static str,
+	body: String,
+}
+
+impl Response {
+	fn ok(content_type: &'static str, body: String) -> Response {
+		Response {
+			status: 200,
+			content_type,
+			body,
+		}
+	}
+
+	fn error(status: u16, message: &str) -> Response {
+		Response {
+			status,
+			content_type: "application/json",
+			body: serde_json::json!({ "error": message }).to_string(),
+		}
+	}
+
+	fn write_to(&self, stream: &mut impl Write) -> io::Result<()> {
+		let reason = match self.status {
+			200 => "OK",
+			400 => "Bad Request",
+			404 => "Not Found",
+			405 => "Method Not Allowed",
+			_ => "Internal Server Error",
+		};
+		write!(
+			stream,
+			"HTTP/1.1 {} {reason}\r\n\
+			Content-Type: {}; charset=utf-8\r\n\
+			Content-Length: {}\r\n\
+			Access-Control-Allow-Origin: *\r\n\
+			Allow: GET\r\n\
+			Connection: close\r\n\r\n{}",
+			self.status,
+			self.content_type,
+			self.body.len(),
+			self.body
+		)
+	}
+}
+
+struct Server<'a> {
+	group_name: &'a str,
+	repos: &'a WorkingGroupInfo,
+	backend: Backend,
+	cache: HashMap<String, (Instant, Vec<Item>)>,
+	verbose: &'a bool,
+}
+
+impl Server<'_> {
+	fn respond(&mut self, method: &str, target: &str) -> Response {
+		if method != "GET" {
+			return Response::error(405, "only GET requests are supported");
+		}
+
+		let (path, query_string) = target.split_once('?').unwrap_or((target, ""));
+		let Some((endpoint, is_atom)) = route(path) else {
+			return Response::error(
+				404,
+				"expected one of: /actions, /specs, /comments (add '.atom' for a feed)",
+			);
+		};
+		let query = match Query::parse(endpoint, query_string) {
+			Ok(query) => query,
+			Err(message) => return Response::error(400, &message),
+		};
+
+		let items = match self.items(endpoint, &query) {
+			Ok(items) => items,
+			Err(response) => return response,
+		};
+		let items: Vec<Item> = items
+			.into_iter()
+			.filter(|item| query.matches(item))
+			.collect();
+
+		if is_atom {
+			Response::ok(
+				"application/atom+xml",
+				atom(
+					&format!("urn:nu-tracker:{}:{}", self.group_name, endpoint.name()),
+					&format!("'{}' {}", self.group_name, endpoint.name()),
+					&items,
+					&Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+				),
+			)
+		} else {
+			Response::ok(
+				"application/json",
+				serde_json::to_string_pretty(&items).expect("should be able to serialise items"),
+			)
+		}
+	}
+
+	/// Get the items for a query, from the cache if they were fetched recently enough
+	fn items(&mut self, endpoint: Endpoint, query: &Query) -> Result<Vec<Item>, Response> {
+		let max_age = match &self.backend {
+			Backend::GitHub(max_age) => *max_age,
+			Backend::Fixtures(dir) => {
+				let path = dir.join(format!("{}.json", endpoint.name()));
+				let read = fs::read_to_string(&path).map_err(|error| error.to_string());
+				return read
+					.and_then(|json| serde_json::from_str(&json).map_err(|error| error.to_string()))
+					.map_err(|error| {
+						Response::error(500, &format!("can't read fixture {path:?}: {error}"))
+					});
+			}
+		};
+
+		let key = format!("{endpoint:?} {query:?}");
+		self.cache
+			.retain(|_, (fetched, _)| fetched.elapsed() < max_age);
+		if let Some((_, items)) = self.cache.get(&key) {
+			return Ok(items.clone());
+		}
+
+		let items = self.fetch(endpoint, query)?;
+		self.cache.insert(key, (Instant::now(), items.clone()));
+		Ok(items)
+	}
+
+	fn fetch(&self, endpoint: Endpoint, query: &Query) -> Result<Vec<Item>, Response> {
+		if endpoint == Endpoint::Actions {
+			if let Some(unknown) = query
+				.tf
+				.iter()
+				.flatten()
+				.find(|tf| !self.repos.task_forces.contains_key(*tf))
+			{
+				return Err(Response::error(400, &format!("no TF called '{unknown}'")));
+			}
+			// Unlike with the sub-commands, choosing repos is optional: the WG's are the default.
+			let wg = query.wg || query.tf.is_none();
+			// e.g. asking for all TFs' actions, for a group with no TFs
+			let repo_args = get_query_repos_args(
+				&[(self.group_name, self.repos)],
+				&query.main,
+				&wg,
+				&query.tf,
+			)
+			.map_err(|error| Response::error(400, &error))?;
+			return try_query_actions_in_repos(
+				repo_args,
+				&query.assignee,
+				&query.closed,
+				&query.filters(),
+				self.verbose,
+			)
+			.map(|actions| actions.into_iter().map(Item::from).collect())
+			.map_err(|error| self.github_error(error));
+		}
+
+		let Some(horizontal_review) = &self.repos.horizontal_review else {
+			return Err(Response::error(
+				404,
+				&format!(
+					"group '{}' is not a horizontal review group",
+					self.group_name
+				),
+			));
+		};
+
+		match endpoint {
+			Endpoint::Specs => {
+				try_query_review_requests(&horizontal_review.specs, &query.assignee, self.verbose)
+					.map(|requests| {
+						requests
+							.into_iter()
+							.map(|request| Item::from_review(&horizontal_review.specs, request))
+							.collect()
+					})
+			}
+			_ => {
+				let status = match &query.status {
+					Some(flags) => flags.parse().expect("status should've been checked"),
+					None => LabelStringList::default(),
+				};
+				try_query_comment_requests(
+					&horizontal_review.comments,
+					&status,
+					&query.assignee,
+					None,
+					self.verbose,
+				)
+				.map(|requests| {
+					requests
+						.into_iter()
+						.map(|request| {
+							Item::from_comment_request(&horizontal_review.comments, request)
+						})
+						.collect()
+				})
+			}
+		}
+		.map_err(|error| self.github_error(error))
+	}
+
+	/// Failures to run 'gh' are reported to the client (and logged, if verbose), rather than
+	/// stopping the server
+	fn github_error(&self, error: String) -> Response {
+		if *self.verbose {
+			println!("Serve: {error}")
+		}
+		Response::error(500, "couldn't get the data from GitHub")
+	}
+
+	fn handle(&mut self, stream: &mut TcpStream) -> io::Result<()> {
+		stream.set_read_timeout(Some(READ_TIMEOUT))?;
+		let mut reader = BufReader::new(stream.try_clone()?);
+		let mut request_line = String::new();
+		reader.read_line(&mut request_line)?;
+
+		// The headers aren't needed, but are read so that the client isn't cut off.
+		let mut header = String::new();
+		while reader.read_line(&mut header)? > 2 {
+			header.clear()
+		}
+
+		let mut parts = request_line.split_whitespace();
+		let response = match (parts.next(), parts.next()) {
+			(Some(method), Some(target)) => self.respond(method, target),
+			_ => Response::error(400, "malformed request"),
+		};
+		if *self.verbose {
+			println!("Serve: {} {}", request_line.trim(), response.status)
+		}
+		response.write_to(stream)
+	}
+}
+
+/// Serve the WG's actions, spec review requests and comment requests as JSON, or Atom feeds
+///
+/// The query parameters mirror the corresponding sub-commands' options. Requests are handled one
+/// at a time.
+pub fn serve(
+	group_name: &str,
+	repos: &WorkingGroupInfo,
+	address: &str,
+	port: u16,
+	backend: Backend,
+	verbose: &bool,
+) -> io::Result<()> {
+	let listener = TcpListener::bind((address, port))?;
+	println!(
+		"Serving '{group_name}' on http://{}/ (/actions, /specs and /comments; add '.atom' for a feed)",
+		listener.local_addr()?
+	);
+
+	let mut server = Server {
+		group_name,
+		repos,
+		backend,
+		cache: HashMap::new(),
+		verbose,
+	};
+	for stream in listener.incoming() {
+		let result = stream.and_then(|mut stream| server.handle(&mut stream));
+		if let Err(error) = result {
+			eprintln!("Serve: {error}")
+		}
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn fixtures(name: &str) -> PathBuf {
+		let dir = std::env::temp_dir().join(format!("nt-serve-{name}-{}", std::process::id()));
+		fs::create_dir_all(&dir).unwrap();
+		fs::write(
+			dir.join("actions.json"),
+			r#"[
+				{
+					"locator": "w3c/apa#1",
+					"url": "https://github.com/w3c/apa/issues/1",
+					"title": "Review the spec",
+					"state": "open",
+					"assignees": ["alice"],
+					"labels": ["action", "aria"],
+					"due": "2024-01-17",
+					"updated": "2024-01-02T09:00:00Z"
+				},
+				{
+					"locator": "w3c/apa#2",
+					"url": "https://github.com/w3c/apa/issues/2",
+					"title": "Write the note",
+					"state": "closed",
+					"assignees": ["bob"],
+					"updated": "2024-01-03T09:00:00Z"
+				}
+			]"#,
+		)
+		.unwrap();
+		dir
+	}
+
+	fn respond(dir: &std::path::Path, target: &str) -> Response {
+		respond_from(Backend::Fixtures(dir.to_path_buf()), target)
+	}
+
+	fn respond_from(backend: Backend, target: &str) -> Response {
+		let repos: WorkingGroupInfo = serde_json::from_value(serde_json::json!({
+			"workingGroup": { "main": "w3c/apa" },
+			"taskForces": {},
+		}))
+		.unwrap();
+		let mut server = Server {
+			group_name: "apa",
+			repos: &repos,
+			backend,
+			cache: HashMap::new(),
+			verbose: &false,
+		};
+		server.respond("GET", target)
+	}
+
+	fn locators(response: &Response) -> Vec<String> {
+		let items: Vec<Item> = serde_json::from_str(&response.body).unwrap();
+		items.into_iter().map(|item| item.locator).collect()
+	}
+
+	#[test]
+	fn from_fixtures() {
+		let dir = fixtures("items");
+
+		let response = respond(&dir, "/actions");
+		assert_eq!(response.status, 200);
+		assert_eq!(locators(&response), vec!["w3c/apa#1"]);
+		assert_eq!(
+			locators(&respond(&dir, "/actions?closed&assignee=bob")),
+			vec!["w3c/apa#2"]
+		);
+		assert!(locators(&respond(&dir, "/actions?label=aria&label=cg")).is_empty());
+
+		let feed = respond(&dir, "/actions.atom?closed");
+		assert_eq!(feed.content_type, "application/atom+xml");
+		assert_eq!(feed.body.matches("<entry>").count(), 2);
+
+		assert_eq!(respond(&dir, "/specs").status, 500);
+		fs::remove_dir_all(dir).unwrap();
+	}
+
+	#[test]
+	fn errors() {
+		let dir = fixtures("errors");
+		assert_eq!(respond(&dir, "/").status, 404);
+		assert_eq!(respond(&dir, "/actions?colour=red").status, 400);
+		let mut out = vec![];
+		respond(&dir, "/nope").write_to(&mut out).unwrap();
+		assert!(String::from_utf8(out)
+			.unwrap()
+			.starts_with("HTTP/1.1 404 Not Found\r\n"));
+		fs::remove_dir_all(dir).unwrap();
+	}
+
+	#[test]
+	fn no_repos_selected() {
+		// The group has no TFs, so this is caught before 'gh' would be run
+		let response = respond_from(Backend::GitHub(Duration::ZERO), "/actions?tf");
+		assert_eq!(response.status, 400);
+		assert!(response.body.contains("No repos selected"));
+	}
+}
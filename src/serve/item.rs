@@ -0,0 +1,224 @@
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+
+use crate::comments::CommentReviewRequest;
+use crate::flatten_assignees::UNASSIGNED;
+use crate::issues_actions::DatedAction;
+use crate::locator::Locator;
+use crate::specs::ReviewRequest;
+
+/// An action, spec review request or comment request, as served
+///
+/// Fixture files contain lists of these, in the same JSON format.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(super) struct Item {
+	pub locator: String,
+	pub url: String,
+	pub title: String,
+	pub state: String,
+	pub assignees: Vec<String>,
+	#[serde(default)]
+	pub labels: Vec<String>,
+	#[serde(default)]
+	pub author: Option<String>,
+	#[serde(default)]
+	pub due: Option<String>,
+	/// Comment requests' status flags (e.g. "T N")
+	#[serde(default)]
+	pub status: Option<String>,
+	/// The group that made a comment request
+	#[serde(default)]
+	pub source: Option<String>,
+	/// The issue that a comment request is about
+	#[serde(default)]
+	pub source_issue: Option<String>,
+	#[serde(default)]
+	pub created: Option<String>,
+	pub updated: String,
+}
+
+fn url(repo: &str, number: u32) -> String {
+	Locator::from_name_with_owner(repo, number)
+		.expect("should be able to make a Locator from returned issue")
+		.url()
+}
+
+impl From<DatedAction> for Item {
+	fn from(dated: DatedAction) -> Self {
+		let action = dated.action;
+		let repo = action.repository.name_with_owner;
+		Item {
+			locator: format!("{repo}#{}", action.number),
+			url: url(&repo, action.number),
+			title: action.title,
+			state: action.state,
+			assignees: action
+				.assignees
+				.into_iter()
+				.map(|assignee| assignee.login)
+				.collect(),
+			labels: action.labels.into_iter().map(|label| label.name).collect(),
+			author: action.author.map(|author| author.login),
+			due: dated.due.map(|due| due.to_string()),
+			status: None,
+			source: None,
+			source_issue: None,
+			created: Some(action.created_at),
+			updated: action.updated_at,
+		}
+	}
+}
+
+impl Item {
+	pub fn from_review(repo: &str, request: ReviewRequest) -> Item {
+		Item {
+			locator: format!("{repo}#{}", request.number),
+			url: url(repo, request.number),
+			title: request.spec,
+			state: String::from("open"),
+			assignees: request
+				.assignees
+				.into_iter()
+				.map(|assignee| assignee.login)
+				.collect(),
+			labels: vec![],
+			author: None,
+			due: Some(request.due.to_string()),
+			status: None,
+			source: None,
+			source_issue: None,
+			created: None,
+			updated: request.updated_at,
+		}
+	}
+
+	pub fn from_comment_request(repo: &str, request: CommentReviewRequest) -> Item {
+		Item {
+			locator: format!("{repo}#{}", request.tracking_number),
+			url: url(repo, request.tracking_number),
+			title: request.title,
+			state: String::from("open"),
+			assignees: request
+				.tracking_assignees
+				.split(',')
+				.filter(|login| *login != UNASSIGNED)
+				.map(String::from)
+				.collect(),
+			labels: vec![],
+			author: None,
+			due: None,
+			status: Some(request.status.to_string()),
+			source: request.source_label.map(|label| label.to_string()),
+			source_issue: Some(request.source_issue),
+			created: None,
+			updated: request.updated_at,
+		}
+	}
+
+	fn summary(&self) -> String {
+		let mut parts = vec![];
+		if let Some(due) = &self.due {
+			parts.push(format!("due {due}"))
+		}
+		if self.assignees.is_empty() {
+			parts.push(String::from("unassigned"))
+		} else {
+			parts.push(format!("assigned to {}", self.assignees.join(", ")))
+		}
+		if let Some(status) = self.status.as_ref().filter(|status| !status.is_empty()) {
+			parts.push(format!("status: {status}"))
+		}
+		if let Some(source) = &self.source {
+			parts.push(format!("from {source}"))
+		}
+		parts.join("; ")
+	}
+}
+
+fn escape(text: &str) -> String {
+	text.replace('&', "&amp;")
+		.replace('<', "&lt;")
+		.replace('>', "&gt;")
+		.replace('"', "&quot;")
+}
+
+/// Make an Atom feed of the items, with the most recently updated first
+///
+/// `now` is used as the feed's update time if there are no items.
+pub(super) fn atom(id: &str, title: &str, items: &[Item], now: &str) -> String {
+	let mut items: Vec<&Item> = items.iter().collect();
+	items.sort_by(|a, b| b.updated.cmp(&a.updated));
+	let updated = items.first().map_or(now, |item| &item.updated);
+
+	let mut out = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+	out.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+	writeln!(out, "<id>{}</id>", escape(id)).unwrap();
+	writeln!(out, "<title>{}</title>", escape(title)).unwrap();
+	writeln!(out, "<updated>{}</updated>", escape(updated)).unwrap();
+	out.push_str("<author><name>Nu Tracker</name></author>\n");
+	for item in items {
+		out.push_str("<entry>\n");
+		writeln!(out, "<id>{}</id>", escape(&item.url)).unwrap();
+		writeln!(
+			out,
+			"<title>{}: {}</title>",
+			escape(&item.locator),
+			escape(&item.title)
+		)
+		.unwrap();
+		writeln!(out, "<link href=\"{}\"/>", escape(&item.url)).unwrap();
+		writeln!(out, "<updated>{}</updated>", escape(&item.updated)).unwrap();
+		writeln!(out, "<summary>{}</summary>", escape(&item.summary())).unwrap();
+		out.push_str("</entry>\n");
+	}
+	out.push_str("</feed>\n");
+	out
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn feed() {
+		let item = |number: u32, updated: &str| Item {
+			locator: format!("w3c/apa#{number}"),
+			url: format!("https://github.com/w3c/apa/issues/{number}"),
+			title: String::from("Check <video> & <audio>"),
+			state: String::from("open"),
+			assignees: vec![String::from("alice")],
+			labels: vec![],
+			author: None,
+			due: Some(String::from("2024-01-17")),
+			status: None,
+			source: None,
+			source_issue: None,
+			created: None,
+			updated: updated.to_string(),
+		};
+		let feed = atom(
+			"urn:nt:apa:actions",
+			"'apa' actions",
+			&[
+				item(1, "2024-01-01T09:00:00Z"),
+				item(2, "2024-01-02T09:00:00Z"),
+			],
+			"2024-02-01T00:00:00Z",
+		);
+
+		assert!(feed.contains("<updated>2024-01-02T09:00:00Z</updated>\n<author>"));
+		assert!(feed.contains("<title>w3c/apa#2: Check &lt;video&gt; &amp; &lt;audio&gt;</title>"));
+		assert!(feed.contains("<summary>due 2024-01-17; assigned to alice</summary>"));
+		assert!(feed.find("w3c/apa#2").unwrap() < feed.find("w3c/apa#1").unwrap());
+		assert_eq!(feed.matches("<entry>").count(), 2);
+
+		assert!(atom(
+			"urn:nt:apa:specs",
+			"'apa' specs",
+			&[],
+			"2024-02-01T00:00:00Z"
+		)
+		.contains("<updated>2024-02-01T00:00:00Z</updated>"));
+	}
+}
@@ -0,0 +1,279 @@
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+
+use crate::filters::Filters;
+use crate::status::LabelStringList;
+
+use super::item::Item;
+
+/// What can be requested
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) enum Endpoint {
+	Actions,
+	Specs,
+	Comments,
+}
+
+impl Endpoint {
+	pub fn name(&self) -> &'static str {
+		match self {
+			Endpoint::Actions => "actions",
+			Endpoint::Specs => "specs",
+			Endpoint::Comments => "comments",
+		}
+	}
+
+	/// The parameters that are accepted, named after the corresponding sub-command's options
+	fn parameters(&self) -> &'static [&'static str] {
+		match self {
+			Endpoint::Actions => &[
+				"assignee",
+				"closed",
+				"main",
+				"wg",
+				"tf",
+				"label",
+				"milestone",
+				"author",
+				"mentions",
+				"search",
+				"updated-since",
+				"created-since",
+			],
+			Endpoint::Specs => &["assignee"],
+			Endpoint::Comments => &["assignee", "status"],
+		}
+	}
+}
+
+/// A request's path (e.g. "/actions.atom"): the endpoint, and whether an Atom feed is wanted
+pub(super) fn route(path: &str) -> Option<(Endpoint, bool)> {
+	let (name, atom) = match path.trim_start_matches('/').split_once('.') {
+		Some((name, "atom")) => (name, true),
+		Some((name, "json")) => (name, false),
+		Some(_) => return None,
+		None => (path.trim_start_matches('/'), false),
+	};
+	let endpoint = match name {
+		"actions" => Endpoint::Actions,
+		"specs" => Endpoint::Specs,
+		"comments" => Endpoint::Comments,
+		_ => return None,
+	};
+	Some((endpoint, atom))
+}
+
+/// The options given in a request's query string
+#[derive(Debug, Default, PartialEq)]
+pub(super) struct Query {
+	pub assignee: Option<String>,
+	pub closed: bool,
+	pub main: bool,
+	pub wg: bool,
+	/// All TFs if given with no value; otherwise a comma-separated list
+	pub tf: Option<Vec<String>>,
+	pub labels: Vec<String>,
+	pub milestone: Option<String>,
+	pub author: Option<String>,
+	pub mentions: Option<String>,
+	pub search: Option<String>,
+	pub updated_since: Option<NaiveDate>,
+	pub created_since: Option<NaiveDate>,
+	/// Status flags (e.g. "TN"); checked when the query is parsed
+	pub status: Option<String>,
+}
+
+impl Query {
+	pub fn parse(endpoint: Endpoint, query_string: &str) -> Result<Query, String> {
+		let mut query = Query::default();
+
+		for pair in query_string.split('&').filter(|pair| !pair.is_empty()) {
+			let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+			let name = decode(name);
+			let value = decode(value);
+
+			if !endpoint.parameters().contains(&name.as_str()) {
+				return Err(format!(
+					"unknown parameter '{name}' for /{}; expected one of: {}",
+					endpoint.name(),
+					endpoint.parameters().join(", ")
+				));
+			}
+
+			let flag = |value: &str| match value {
+				"" | "true" => Ok(true),
+				"false" => Ok(false),
+				_ => Err(format!("expected 'true' or 'false' for '{name}'")),
+			};
+			let date = |value: &str| {
+				NaiveDate::parse_from_str(value, "%Y-%m-%d")
+					.map_err(|_| format!("expected a date (e.g. '2023-05-23') for '{name}'"))
+			};
+			let text = |value: String| {
+				if value.is_empty() {
+					Err(format!("expected a value for '{name}'"))
+				} else {
+					Ok(Some(value))
+				}
+			};
+
+			match name.as_str() {
+				"assignee" => query.assignee = text(value)?,
+				"closed" => query.closed = flag(&value)?,
+				"main" => query.main = flag(&value)?,
+				"wg" => query.wg = flag(&value)?,
+				"tf" => {
+					query.tf = Some(
+						value
+							.split(',')
+							.filter(|tf| !tf.is_empty())
+							.map(String::from)
+							.collect(),
+					)
+				}
+				"label" => query.labels.push(text(value)?.unwrap()),
+				"milestone" => query.milestone = text(value)?,
+				"author" => query.author = text(value)?,
+				"mentions" => query.mentions = text(value)?,
+				"search" => query.search = text(value)?,
+				"updated-since" => query.updated_since = Some(date(&value)?),
+				"created-since" => query.created_since = Some(date(&value)?),
+				"status" => {
+					LabelStringList::from_str(&value).map_err(|error| error.to_string())?;
+					query.status = text(value)?
+				}
+				_ => unreachable!("unknown parameter '{name}'"),
+			}
+		}
+
+		Ok(query)
+	}
+
+	pub fn filters(&self) -> Filters {
+		Filters {
+			labels: self.labels.clone(),
+			milestone: self.milestone.clone(),
+			author: self.author.clone(),
+			mentions: self.mentions.clone(),
+			search: self.search.clone(),
+			updated_since: self.updated_since,
+			created_since: self.created_since,
+			closed_since: None,
+			limit: None,
+		}
+	}
+
+	/// Whether an item meets the criteria that can be checked locally
+	///
+	/// As with the sub-commands, the repos, milestone, mentions and search text can only be
+	/// checked by GitHub (so aren't applied to fixtures).
+	pub fn matches(&self, item: &Item) -> bool {
+		// '@me' can only be resolved by GitHub.
+		fn checkable(user: &Option<String>) -> Option<&String> {
+			user.as_ref().filter(|user| *user != "@me")
+		}
+		let on_or_after = |timestamp: Option<&str>, since: Option<NaiveDate>| match since {
+			Some(since) => timestamp
+				.and_then(|timestamp| timestamp.get(..10))
+				.is_some_and(|date| date >= since.to_string().as_str()),
+			None => true,
+		};
+		let has_flags = |status: &str| {
+			let flags: Vec<&str> = item
+				.status
+				.as_deref()
+				.unwrap_or_default()
+				.split_whitespace()
+				.collect();
+			status
+				.chars()
+				.all(|flag| flags.contains(&flag.to_string().as_str()))
+		};
+
+		(self.closed || item.state == "open")
+			&& checkable(&self.assignee).is_none_or(|user| item.assignees.contains(user))
+			&& checkable(&self.author).is_none_or(|user| item.author.as_ref() == Some(user))
+			&& self.labels.iter().all(|label| item.labels.contains(label))
+			&& self.status.as_deref().is_none_or(has_flags)
+			&& on_or_after(Some(&item.updated), self.updated_since)
+			&& on_or_after(item.created.as_deref(), self.created_since)
+	}
+}
+
+/// Decode a percent-encoded query string name or value
+fn decode(text: &str) -> String {
+	let bytes = text.as_bytes();
+	let mut out = Vec::with_capacity(bytes.len());
+	let mut index = 0;
+	while index < bytes.len() {
+		match bytes[index] {
+			b'+' => out.push(b' '),
+			b'%' => match text
+				.get(index + 1..index + 3)
+				.and_then(|hex| u8::from_str_radix(hex, 16).ok())
+			{
+				Some(byte) => {
+					out.push(byte);
+					index += 2
+				}
+				None => out.push(b'%'),
+			},
+			byte => out.push(byte),
+		}
+		index += 1
+	}
+	String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn routes() {
+		assert_eq!(route("/actions"), Some((Endpoint::Actions, false)));
+		assert_eq!(route("/specs.json"), Some((Endpoint::Specs, false)));
+		assert_eq!(route("/comments.atom"), Some((Endpoint::Comments, true)));
+		assert_eq!(route("/comments.rss"), None);
+		assert_eq!(route("/"), None);
+	}
+
+	#[test]
+	fn decoding() {
+		assert_eq!(decode("needs+resolution"), "needs resolution");
+		assert_eq!(decode("%40me"), "@me");
+		assert_eq!(decode("caf%C3%A9"), "café");
+		assert_eq!(decode("100%"), "100%");
+	}
+
+	#[test]
+	fn parsing() {
+		let query = Query::parse(
+			Endpoint::Actions,
+			"assignee=%40me&tf&label=a&label=b+c&updated-since=2024-01-01&closed",
+		)
+		.unwrap();
+		assert_eq!(
+			query,
+			Query {
+				assignee: Some(String::from("@me")),
+				closed: true,
+				tf: Some(vec![]),
+				labels: vec![String::from("a"), String::from("b c")],
+				updated_since: NaiveDate::from_ymd_opt(2024, 1, 1),
+				..Query::default()
+			}
+		);
+		assert_eq!(
+			Query::parse(Endpoint::Actions, "tf=aria,,cg").unwrap().tf,
+			Some(vec![String::from("aria"), String::from("cg")])
+		);
+
+		assert!(Query::parse(Endpoint::Specs, "label=a").is_err());
+		assert!(Query::parse(Endpoint::Actions, "closed=yes").is_err());
+		assert!(Query::parse(Endpoint::Actions, "updated-since=soon").is_err());
+		assert!(Query::parse(Endpoint::Comments, "status=TN").is_ok());
+		assert!(Query::parse(Endpoint::Comments, "status=Z").is_err());
+	}
+}
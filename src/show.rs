@@ -0,0 +1,173 @@
+use std::{process::Command, str::FromStr};
+
+use serde::Deserialize;
+use struct_field_names_as_array::FieldNamesAsArray;
+
+use crate::gh;
+use crate::issues_actions::get_due;
+use crate::locator::{ItemKind, Locator};
+use crate::specs::review_due;
+use crate::status::{Status, StatusLabel};
+
+/// An issue or PR, as returned by `gh issue view` or `gh pr view`
+///
+/// The users and labels returned by these commands have different fields from those returned by
+/// `gh search issues`, so only the ones needed are picked out.
+#[derive(Deserialize, FieldNamesAsArray)]
+#[serde(rename_all = "camelCase")]
+#[field_names_as_array(rename_all = "camelCase")]
+struct ViewedIssue {
+	assignees: Vec<User>,
+	author: Option<User>,
+	body: String,
+	comments: Vec<IssueComment>,
+	labels: Vec<NamedLabel>,
+	state: String,
+	title: String,
+	updated_at: String,
+	url: String,
+}
+
+#[derive(Deserialize)]
+struct User {
+	login: String,
+}
+
+#[derive(Deserialize)]
+struct NamedLabel {
+	name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IssueComment {
+	author: Option<User>,
+	body: String,
+	created_at: String,
+}
+
+/// Fetch an issue or PR, and print its details, and its last `comments` comments
+pub fn show(locator: &Locator, comments: usize, verbose: &bool) {
+	let kind = match locator.kind() {
+		ItemKind::Issue => "issue",
+		ItemKind::PullRequest => "pr",
+	};
+
+	let mut cmd = Command::new("gh");
+	cmd.args([kind, "view", &locator.number().to_string()])
+		.args(["--repo", &locator.name_with_owner()])
+		.args(["--json", &ViewedIssue::FIELD_NAMES_AS_ARRAY.join(",")]);
+
+	if *verbose {
+		println!("Show: running: {cmd:?}");
+	}
+	let issue: ViewedIssue = gh::run_json(&mut cmd).unwrap_or_else(gh::fail);
+	print!("{}", render(locator, &issue, comments))
+}
+
+fn render(locator: &Locator, issue: &ViewedIssue, comments: usize) -> String {
+	let mut out = format!("{locator}: {}\n", issue.title);
+	let mut field = |name: &str, value: &str| {
+		if !value.is_empty() {
+			out.push_str(&format!("{name:<10} {value}\n"))
+		}
+	};
+
+	let logins = |users: &[User]| -> String {
+		let names: Vec<&str> = users.iter().map(|user| user.login.as_str()).collect();
+		names.join(", ")
+	};
+	let label_names: Vec<&str> = issue
+		.labels
+		.iter()
+		.map(|label| label.name.as_str())
+		.collect();
+
+	let mut status = Status::new();
+	for name in &label_names {
+		if let Ok(label) = StatusLabel::from_str(name) {
+			status.add(label)
+		}
+	}
+
+	// Actions give their due dates in the body; spec review requests in the title.
+	let due = get_due(&issue.body).or_else(|| review_due(&issue.title));
+
+	field("State:", &issue.state);
+	field("URL:", &issue.url);
+	field(
+		"Author:",
+		issue.author.as_ref().map_or("", |author| &author.login),
+	);
+	field("Assignees:", &logins(&issue.assignees));
+	field("Labels:", &label_names.join(", "));
+	field("Status:", &status.to_string());
+	field(
+		"Due:",
+		&due.map(|date| date.to_string()).unwrap_or_default(),
+	);
+	field("Updated:", issue.updated_at.get(..10).unwrap_or_default());
+
+	if !issue.body.trim().is_empty() {
+		out.push_str(&format!("\n{}\n", issue.body.trim()))
+	}
+
+	let shown = issue.comments.len().min(comments);
+	if shown > 0 {
+		out.push_str(&format!(
+			"\nLast {shown} of {} comments:\n",
+			issue.comments.len()
+		));
+		for comment in &issue.comments[issue.comments.len() - shown..] {
+			out.push_str(&format!(
+				"\n--- {} on {}\n{}\n",
+				comment
+					.author
+					.as_ref()
+					.map_or("(unknown)", |author| &author.login),
+				comment.created_at.get(..10).unwrap_or_default(),
+				comment.body.trim()
+			))
+		}
+	}
+
+	out
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn rendering() {
+		let issue: ViewedIssue = serde_json::from_str(
+			r#"{
+				"assignees": [{ "id": "1", "login": "a", "name": "A" }],
+				"author": { "id": "2", "login": "b", "name": "B", "is_bot": false },
+				"body": "due 23 May 2027\n",
+				"comments": [
+					{ "author": { "login": "c" }, "body": "First", "createdAt": "2023-05-02T09:00:00Z" },
+					{ "author": { "login": "d" }, "body": "Second", "createdAt": "2023-05-03T09:00:00Z" }
+				],
+				"labels": [
+					{ "id": "1", "name": "action", "description": "", "color": "fff" },
+					{ "id": "2", "name": "pending", "description": "", "color": "fff" }
+				],
+				"state": "OPEN",
+				"title": "Do the thing",
+				"updatedAt": "2023-05-03T09:00:00Z",
+				"url": "https://github.com/w3c/apa/issues/42"
+			}"#,
+		)
+		.unwrap();
+		let locator = Locator::from_str("w3c/apa#42").unwrap();
+
+		let text = render(&locator, &issue, 1);
+		assert!(text.starts_with("w3c/apa#42: Do the thing\nState:     OPEN\n"));
+		assert!(text.contains("Labels:    action, pending\n"));
+		assert!(text.contains("Status:    P\n"));
+		assert!(text.contains("Due:       2027-05-23\n"));
+		assert!(text.contains("Last 1 of 2 comments:\n\n--- d on 2023-05-03\nSecond\n"));
+		assert!(!text.contains("First"));
+	}
+}
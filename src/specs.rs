@@ -3,7 +3,6 @@
 // TODO: What to do if the given start date doesn't match the issue title?
 // FIXME: How to report ones that didn't parse?
 
-use std::io::{self, Write};
 use std::{println, process::Command, str};
 
 use chrono::{Days, NaiveDate};
@@ -11,9 +10,13 @@ use regex::Regex;
 
 use crate::config::WorkingGroupInfo;
 use crate::flatten_assignees::flatten_assignees;
-use crate::make_table::make_table;
-use crate::returned_issue::ReturnedIssueLight;
+use crate::gh;
+use crate::locator::Locator;
+use crate::returned_issue::{Assignee, ReturnedIssueLight};
 use crate::showing::showing;
+use crate::table_row::{
+	check_columns, render_sorted, sort_rows, SortBy, SortKey, TableOptions, TableRow,
+};
 
 const DEFAULT_REVIEW_TIME: u64 = 21;
 
@@ -23,38 +26,140 @@ struct SpecAndDue {
 	due: NaiveDate,
 }
 
-#[derive(Debug)]
-struct ReviewRequest {
-	spec: String,
-	due: NaiveDate,
-	number: u32,
-	assignees: String,
+pub(crate) struct ReviewRequest {
+	pub spec: String,
+	pub due: NaiveDate,
+	pub number: u32,
+	pub assignees: Vec<Assignee>,
+	pub updated_at: String,
 }
 
-impl ReviewRequest {
-	// TODO: Make trait?
-	fn to_vec_string(&self) -> Vec<String> {
-		vec![
-			format!("{}", self.due),
-			self.number.to_string(),
-			self.spec.to_string(),
-			self.assignees.to_string(),
-		]
+impl TableRow for ReviewRequest {
+	const COLUMNS: &'static [&'static str] = &["due", "id", "spec", "assignees", "updated"];
+
+	fn cell(&self, column: &str) -> String {
+		match column {
+			"due" => format!("{}", self.due),
+			"id" => self.number.to_string(),
+			"spec" => self.spec.to_string(),
+			"assignees" => flatten_assignees(&self.assignees),
+			"updated" => self.updated_at.chars().take(10).collect(),
+			_ => unreachable!("unknown column '{column}'"),
+		}
+	}
+
+	fn sort_key(&self, by: SortBy) -> SortKey {
+		match by {
+			SortBy::Due => SortKey::date(Some(self.due)),
+			// All requests are in the same repo.
+			SortBy::Repo => SortKey::Text(String::new()),
+			SortBy::Assignee => SortKey::Text(flatten_assignees(&self.assignees)),
+			SortBy::Number => SortKey::Number(self.number),
+			SortBy::Updated => SortKey::Text(self.updated_at.to_string()),
+		}
 	}
 }
 
 // TODO: DRY with actions, comments?
-/// Query for spec review requests, output a custom report, sorted by due date.
-pub fn specs(group_name: &str, repos: &WorkingGroupInfo, verbose: &bool) {
+/// Query for spec review requests, output a custom report, sorted by due date (unless another
+/// sort order is given). The listed requests are returned, so that they can be opened.
+pub fn specs(
+	group_name: &str,
+	repos: &WorkingGroupInfo,
+	table_options: &TableOptions,
+	verbose: &bool,
+) -> Vec<Locator> {
 	if repos.horizontal_review.is_none() {
 		println!("Group '{group_name}' is not a horizontal review group.");
-		return;
+		return vec![];
+	}
+
+	if let Err(message) = check_columns::<ReviewRequest>(table_options) {
+		println!("{message}");
+		return vec![];
 	}
 
+	let spec_repo = &repos.horizontal_review.as_ref().unwrap().specs;
+	let reviews = fetch_reviews(spec_repo, &None, verbose);
+
+	// DRY with comments
+	if reviews.is_empty() {
+		// TODO: Make this neater a la .join() for the vec
+		println!("No spec review requests found");
+		return vec![];
+	} else {
+		println!(
+			"{} open review requests in {}\n",
+			showing(reviews.len()),
+			spec_repo
+		)
+	}
+
+	let mut requests = review_requests(reviews);
+	sort_rows(&mut requests, table_options);
+	let listed = requests
+		.iter()
+		.map(|request| {
+			Locator::from_name_with_owner(spec_repo, request.number)
+				.expect("should be able to make a Locator from returned issue")
+		})
+		.collect();
+
+	match render_sorted(
+		&requests,
+		&["due", "id", "spec", "assignees"],
+		table_options,
+		&[],
+	) {
+		Ok(table) => println!("{table}"),
+		Err(message) => println!("{message}"),
+	}
+
+	listed
+}
+
+/// Query for open spec review requests in the given repo, and return those with due dates, sorted
+/// by due date.
+pub(crate) fn query_review_requests(
+	spec_repo: &str,
+	assignee: &Option<String>,
+	verbose: &bool,
+) -> Vec<ReviewRequest> {
+	review_requests(fetch_reviews(spec_repo, assignee, verbose))
+}
+
+/// As [`query_review_requests()`], but if `gh` fails, the error is returned, rather than stopping
+pub(crate) fn try_query_review_requests(
+	spec_repo: &str,
+	assignee: &Option<String>,
+	verbose: &bool,
+) -> Result<Vec<ReviewRequest>, String> {
+	try_fetch_reviews(spec_repo, assignee, verbose).map(review_requests)
+}
+
+fn fetch_reviews(
+	spec_repo: &str,
+	assignee: &Option<String>,
+	verbose: &bool,
+) -> Vec<ReturnedIssueLight> {
+	try_fetch_reviews(spec_repo, assignee, verbose).unwrap_or_else(gh::fail)
+}
+
+fn try_fetch_reviews(
+	spec_repo: &str,
+	assignee: &Option<String>,
+	verbose: &bool,
+) -> Result<Vec<ReturnedIssueLight>, String> {
+	let assignee_args: Vec<&str> = match assignee {
+		Some(user) => vec!["--assignee", user],
+		None => vec![],
+	};
+
 	let mut cmd = Command::new("gh");
 	cmd.args(["search", "issues"])
-		.args(["--repo", &repos.horizontal_review.as_ref().unwrap().specs])
+		.args(["--repo", spec_repo])
 		.args(["--state", "open"])
+		.args(assignee_args)
 		.args([
 			"--json",
 			&ReturnedIssueLight::FIELD_NAMES_AS_ARRAY.join(","),
@@ -63,49 +168,21 @@ pub fn specs(group_name: &str, repos: &WorkingGroupInfo, verbose: &bool) {
 	if *verbose {
 		println!("Spec review: running: {cmd:?}");
 	}
-	let output = cmd.output().expect("'gh' should run");
-
-	if output.status.success() {
-		let out = str::from_utf8(&output.stdout).expect("got non-utf8 data from 'gh'");
-		let reviews: Vec<ReturnedIssueLight> = serde_json::from_str(out).unwrap();
+	gh::run_json(&mut cmd)
+}
 
-		// DRY with comments
-		if reviews.is_empty() {
-			// TODO: Make this neater a la .join() for the vec
-			println!("No spec review requests found");
-			return;
+fn review_requests(reviews: Vec<ReturnedIssueLight>) -> Vec<ReviewRequest> {
+	// TODO: idiomatic?
+	let mut review_requests: Vec<ReviewRequest> = vec![];
+	for issue_info in reviews {
+		if let Some(review_request) = make_review_request(issue_info) {
+			review_requests.push(review_request)
 		} else {
-			println!(
-				"{} open review requests in {}\n",
-				showing(reviews.len()),
-				repos.horizontal_review.as_ref().unwrap().specs
-			)
-		}
-
-		// TODO: idiomatic?
-		let mut review_requests: Vec<ReviewRequest> = vec![];
-		for issue_info in reviews {
-			if let Some(review_request) = make_review_request(issue_info) {
-				review_requests.push(review_request)
-			} else {
-				println!()
-			}
+			println!()
 		}
-		review_requests.sort_by_key(|r| r.due);
-
-		// TODO: more functional?
-		let mut rows: Vec<Vec<String>> = vec![];
-		for request in review_requests {
-			rows.push(request.to_vec_string())
-		}
-
-		let table = make_table(vec!["DUE", "ID", "SPEC", "ASSIGNEES"], rows, None);
-		println!("{table}")
-	} else {
-		io::stdout().write_all(&output.stdout).unwrap();
-		io::stderr().write_all(&output.stderr).unwrap();
-		panic!("'gh' did not run successfully")
 	}
+	review_requests.sort_by_key(|r| r.due);
+	review_requests
 }
 
 fn make_review_request(
@@ -113,6 +190,7 @@ fn make_review_request(
 		assignees,
 		number,
 		title,
+		updated_at,
 	}: ReturnedIssueLight,
 ) -> Option<ReviewRequest> {
 	if let Some(SpecAndDue { spec, due }) = spec_and_due(title.as_str()) {
@@ -120,7 +198,8 @@ fn make_review_request(
 			spec,
 			due,
 			number,
-			assignees: flatten_assignees(&assignees),
+			assignees,
+			updated_at,
 		});
 	}
 
@@ -128,6 +207,17 @@ fn make_review_request(
 	None
 }
 
+/// Get the due date from a spec review request's title, if possible
+pub(crate) fn review_due(title: &str) -> Option<NaiveDate> {
+	spec_and_due(title).map(|spec_and_due| spec_and_due.due)
+}
+
+/// Get the spec's name from a spec review request's title (or the whole title, if there are no
+/// dates to remove)
+pub(crate) fn review_spec(title: &str) -> String {
+	spec_and_due(title).map_or_else(|| title.to_string(), |spec_and_due| spec_and_due.spec)
+}
+
 fn spec_and_due(full_spec: &str) -> Option<SpecAndDue> {
 	const DATE_FORMAT: &str = "%Y-%m-%d";
 	let two_dates = Regex::new(r"(\d{4})-(\d{2})-(\d{2}) .?> (\d{4})-(\d{2})-(\d{2})$").unwrap();
@@ -136,10 +226,10 @@ fn spec_and_due(full_spec: &str) -> Option<SpecAndDue> {
 	if let Some(two_date_match) = two_dates.find(full_spec) {
 		if let Some(due_str) = single_date.find(full_spec) {
 			if let Ok(due) = NaiveDate::parse_from_str(due_str.as_str(), DATE_FORMAT) {
-				return Some(SpecAndDue {
+				Some(SpecAndDue {
 					spec: full_spec[0..two_date_match.start()].trim_end().to_string(),
 					due,
-				});
+				})
 			} else {
 				None
 			}
@@ -148,10 +238,10 @@ fn spec_and_due(full_spec: &str) -> Option<SpecAndDue> {
 		}
 	} else if let Some(filed) = single_date.find(full_spec) {
 		if let Ok(filed_date) = NaiveDate::parse_from_str(filed.as_str(), DATE_FORMAT) {
-			return Some(SpecAndDue {
+			Some(SpecAndDue {
 				spec: full_spec[0..filed.start()].trim_end().to_string(),
 				due: filed_date + Days::new(DEFAULT_REVIEW_TIME),
-			});
+			})
 		} else {
 			None
 		}
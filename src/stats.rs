@@ -0,0 +1,366 @@
+use std::{collections::BTreeMap, process::Command, str::FromStr};
+
+use chrono::{DateTime, NaiveDate};
+
+use crate::comments::SourceLabel;
+use crate::config::WorkingGroupInfo;
+use crate::filters::Filters;
+use crate::gh;
+use crate::make_table::make_table;
+use crate::returned_issue::ReturnedDatedIssue;
+use crate::specs::review_due;
+
+const SPEC_REVIEWS: &str = "spec reviews";
+const COMMENT_REQUESTS: &str = "comment requests";
+const NOT_APPLICABLE: &str = "-";
+
+/// A closed review or comment request
+#[derive(Debug, PartialEq)]
+struct Closed {
+	created: NaiveDate,
+	closed: NaiveDate,
+	due: Option<NaiveDate>,
+	source: String,
+}
+
+impl Closed {
+	fn from(issue: &ReturnedDatedIssue, is_review: bool) -> Option<Closed> {
+		let date = |timestamp: &str| {
+			DateTime::parse_from_rfc3339(timestamp)
+				.ok()
+				.map(|date_time| date_time.date_naive())
+		};
+
+		let source = issue
+			.labels
+			.iter()
+			.find_map(|label| SourceLabel::from_str(&label.name).ok())
+			.map_or(String::from(NOT_APPLICABLE), |source| source.to_string());
+
+		Some(Closed {
+			created: date(&issue.created_at)?,
+			closed: date(issue.closed_at.as_deref()?)?,
+			due: if is_review {
+				review_due(&issue.title)
+			} else {
+				None
+			},
+			source,
+		})
+	}
+
+	fn days_to_close(&self) -> i64 {
+		(self.closed - self.created).num_days()
+	}
+}
+
+/// Summary figures for one kind of request
+#[derive(Debug, PartialEq)]
+struct Summary {
+	closed: usize,
+	median_days: Option<f64>,
+	/// Of those with due dates: how many, and how many were closed on or before it
+	on_time: Option<(usize, usize)>,
+}
+
+impl Summary {
+	fn of(requests: &[Closed]) -> Summary {
+		let mut days: Vec<i64> = requests.iter().map(Closed::days_to_close).collect();
+		days.sort();
+		let median_days = match days.len() {
+			0 => None,
+			count if count % 2 == 1 => Some(days[count / 2] as f64),
+			count => Some((days[count / 2 - 1] + days[count / 2]) as f64 / 2.0),
+		};
+
+		let dated: Vec<&Closed> = requests
+			.iter()
+			.filter(|request| request.due.is_some())
+			.collect();
+		let on_time = if dated.is_empty() {
+			None
+		} else {
+			let in_time = dated
+				.iter()
+				.filter(|request| request.due.is_some_and(|due| request.closed <= due))
+				.count();
+			Some((dated.len(), in_time))
+		};
+
+		Summary {
+			closed: requests.len(),
+			median_days,
+			on_time,
+		}
+	}
+
+	fn cells(&self, kind: &str) -> Vec<String> {
+		vec![
+			kind.to_string(),
+			self.closed.to_string(),
+			self.median_days
+				.map_or(String::from(NOT_APPLICABLE), |days| format!("{days:.1}")),
+			self.on_time
+				.map_or(String::from(NOT_APPLICABLE), |(dated, in_time)| {
+					format!(
+						"{:.0}% ({in_time}/{dated})",
+						in_time as f64 * 100.0 / dated as f64
+					)
+				}),
+		]
+	}
+}
+
+/// Number closed in each month (e.g. "2024-01"), per kind of request and source group
+fn monthly(kinds: &[(&str, &[Closed])]) -> BTreeMap<(String, String, String), usize> {
+	let mut counts = BTreeMap::new();
+	for (kind, requests) in kinds {
+		for request in requests.iter() {
+			let month = request.closed.format("%Y-%m").to_string();
+			*counts
+				.entry((month, kind.to_string(), request.source.clone()))
+				.or_insert(0) += 1
+		}
+	}
+	counts
+}
+
+/// Fetch closed spec review and comment requests (those closed on or after `since`, if given), and
+/// report how quickly they were processed: median days to close, the proportion of spec reviews
+/// closed by their due dates, and monthly counts per source group, as tables or CSV.
+///
+/// Up to `limit` of the most recently updated requests of each kind are counted (GitHub can't sort
+/// by closing date).
+pub fn stats(
+	group_name: &str,
+	repos: &WorkingGroupInfo,
+	since: Option<NaiveDate>,
+	limit: u32,
+	csv: bool,
+	verbose: &bool,
+) {
+	let Some(horizontal_review) = &repos.horizontal_review else {
+		println!("Group '{group_name}' is not a horizontal review group.");
+		return;
+	};
+
+	let filters = Filters {
+		closed_since: since,
+		..Default::default()
+	};
+	let closed = |repo: &str, is_review: bool| -> Vec<Closed> {
+		let issues = fetch_dated(repo, Some("closed"), &filters, limit, verbose);
+		if issues.len() >= limit as usize {
+			// Not on stdout, so that the CSV output isn't affected.
+			eprintln!("NOTE: only the {limit} most recently updated closed requests in {repo} are counted (use --limit or --since to change this)")
+		}
+		issues
+			.iter()
+			.filter_map(|issue| Closed::from(issue, is_review))
+			.collect()
+	};
+	let reviews = closed(&horizontal_review.specs, true);
+	let comments = closed(&horizontal_review.comments, false);
+
+	let summary_headers = vec!["requests", "closed", "median days", "on time"];
+	let summary_rows = vec![
+		Summary::of(&reviews).cells(SPEC_REVIEWS),
+		Summary::of(&comments).cells(COMMENT_REQUESTS),
+	];
+
+	let monthly_headers = vec!["month", "requests", "source", "closed"];
+	let monthly_rows: Vec<Vec<String>> =
+		monthly(&[(SPEC_REVIEWS, &reviews), (COMMENT_REQUESTS, &comments)])
+			.into_iter()
+			.map(|((month, kind, source), count)| vec![month, kind, source, count.to_string()])
+			.collect();
+
+	if csv {
+		print!("{}", to_csv(&summary_headers, &summary_rows));
+		println!();
+		print!("{}", to_csv(&monthly_headers, &monthly_rows));
+	} else {
+		let upper = |headers: &[&str]| -> Vec<String> {
+			headers.iter().map(|header| header.to_uppercase()).collect()
+		};
+		let summary_headers = upper(&summary_headers);
+		let monthly_headers = upper(&monthly_headers);
+		println!(
+			"{}\n",
+			make_table(
+				summary_headers.iter().map(String::as_str).collect(),
+				summary_rows,
+				None
+			)
+		);
+		if !monthly_rows.is_empty() {
+			println!(
+				"{}",
+				make_table(
+					monthly_headers.iter().map(String::as_str).collect(),
+					monthly_rows,
+					None
+				)
+			)
+		}
+	}
+}
+
+fn to_csv(headers: &[&str], rows: &[Vec<String>]) -> String {
+	let field = |text: &str| {
+		if text.contains([',', '"', '\n']) {
+			format!("\"{}\"", text.replace('"', "\"\""))
+		} else {
+			text.to_string()
+		}
+	};
+
+	let mut out = headers.join(",") + "\n";
+	for row in rows {
+		let fields: Vec<String> = row.iter().map(|cell| field(cell)).collect();
+		out.push_str(&(fields.join(",") + "\n"))
+	}
+	out
+}
+
+/// Fetch the most recently updated issues (in the given state, if any, and matching the filters)
+/// with their creation and closing dates
+pub(crate) fn fetch_dated(
+	repo: &str,
+	state: Option<&str>,
+	filters: &Filters,
+	limit: u32,
+	verbose: &bool,
+) -> Vec<ReturnedDatedIssue> {
+	let state_args: Vec<&str> = match state {
+		Some(state) => vec!["--state", state],
+		None => vec![],
+	};
+
+	let mut cmd = Command::new("gh");
+	cmd.args(["search", "issues"])
+		.args(["--repo", repo])
+		.args(state_args)
+		.args(["--sort", "updated"])
+		.args(["--limit", &limit.to_string()])
+		.args([
+			"--json",
+			&ReturnedDatedIssue::FIELD_NAMES_AS_ARRAY.join(","),
+		])
+		.args(filters.args());
+
+	if *verbose {
+		println!("Stats: running: {cmd:?}");
+	}
+	gh::run_json(&mut cmd).unwrap_or_else(gh::fail)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn issue(title: &str, created: &str, closed: &str, source: Option<&str>) -> ReturnedDatedIssue {
+		let labels = match source {
+			Some(name) => {
+				format!(r#"[{{ "id": "1", "color": "fff", "description": "", "name": "{name}" }}]"#)
+			}
+			None => String::from("[]"),
+		};
+		serde_json::from_str(&format!(
+			r#"{{
+				"closedAt": "{closed}T12:00:00Z",
+				"createdAt": "{created}T09:00:00Z",
+				"labels": {labels},
+				"number": 1,
+				"title": "{title}"
+			}}"#
+		))
+		.unwrap()
+	}
+
+	fn closed(issues: &[ReturnedDatedIssue], is_review: bool) -> Vec<Closed> {
+		issues
+			.iter()
+			.filter_map(|issue| Closed::from(issue, is_review))
+			.collect()
+	}
+
+	#[test]
+	fn review_summary() {
+		let reviews = closed(
+			&[
+				issue(
+					"Spec A 2024-01-01 -> 2024-01-20",
+					"2024-01-01",
+					"2024-01-11",
+					None,
+				),
+				issue(
+					"Spec B 2024-01-01 -> 2024-01-20",
+					"2024-01-01",
+					"2024-01-31",
+					None,
+				),
+				issue("Spec C", "2024-01-01", "2024-01-05", None),
+			],
+			true,
+		);
+
+		let summary = Summary::of(&reviews);
+		assert_eq!(
+			summary,
+			Summary {
+				closed: 3,
+				median_days: Some(10.0),
+				on_time: Some((2, 1)),
+			}
+		);
+		assert_eq!(
+			summary.cells(SPEC_REVIEWS),
+			vec![SPEC_REVIEWS, "3", "10.0", "50% (1/2)"]
+		);
+		assert_eq!(Summary::of(&reviews[..2]).median_days, Some(20.0));
+	}
+
+	#[test]
+	fn monthly_per_source() {
+		let comments = closed(
+			&[
+				issue("A", "2024-01-01", "2024-01-10", Some("s:html")),
+				issue("B", "2024-01-01", "2024-01-20", Some("s:html")),
+				issue("C", "2024-01-01", "2024-02-01", Some("s:css")),
+				issue("D", "2024-01-01", "2024-02-01", None),
+			],
+			false,
+		);
+		assert!(comments.iter().all(|request| request.due.is_none()));
+
+		let key = |month: &str, source: &str| {
+			(
+				month.to_string(),
+				COMMENT_REQUESTS.to_string(),
+				source.to_string(),
+			)
+		};
+		let counts = monthly(&[(COMMENT_REQUESTS, &comments)]);
+		assert_eq!(
+			counts,
+			BTreeMap::from([
+				(key("2024-01", "html"), 2),
+				(key("2024-02", "-"), 1),
+				(key("2024-02", "css"), 1),
+			])
+		);
+	}
+
+	#[test]
+	fn csv() {
+		assert_eq!(
+			to_csv(
+				&["a", "b"],
+				&[vec![String::from("1"), String::from("x, \"y\"")]]
+			),
+			"a,b\n1,\"x, \"\"y\"\"\"\n"
+		);
+	}
+}
@@ -2,7 +2,7 @@ use std::{fmt, str::FromStr};
 
 use super::FlagLabelMap;
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct LabelStringList(InternalList);
 type InternalList = Vec<String>;
 
@@ -0,0 +1,282 @@
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use chrono::NaiveDate;
+
+use crate::make_table::make_table;
+
+/// What to sort the rows of a report by
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SortBy {
+	Due,
+	Repo,
+	Assignee,
+	Number,
+	Updated,
+}
+
+const SORT_NAMES: [(&str, SortBy); 5] = [
+	("due", SortBy::Due),
+	("repo", SortBy::Repo),
+	("assignee", SortBy::Assignee),
+	("number", SortBy::Number),
+	("updated", SortBy::Updated),
+];
+
+impl FromStr for SortBy {
+	type Err = String;
+
+	fn from_str(name: &str) -> Result<Self, Self::Err> {
+		SORT_NAMES
+			.iter()
+			.find(|(known, _)| *known == name)
+			.map(|(_, sort)| *sort)
+			.ok_or_else(|| {
+				let names: Vec<&str> = SORT_NAMES.iter().map(|(known, _)| *known).collect();
+				format!("expected one of: {}", names.join(", "))
+			})
+	}
+}
+
+impl fmt::Display for SortBy {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let (name, _) = SORT_NAMES
+			.iter()
+			.find(|(_, sort)| sort == self)
+			.expect("all sort keys should have names");
+		write!(f, "{name}")
+	}
+}
+
+/// How to present a report: the order of its rows, and which columns to show
+#[derive(Default)]
+pub struct TableOptions {
+	pub sort: Option<SortBy>,
+	pub reverse: bool,
+	pub columns: Option<Vec<String>>,
+}
+
+/// A value to sort rows by
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum SortKey {
+	/// Whether there's no date, then the date, so undated rows go last; see [`SortKey::date()`]
+	Date(bool, Option<NaiveDate>),
+	Number(u32),
+	Text(String),
+}
+
+impl SortKey {
+	/// Sort by a date, putting undated rows after the dated ones
+	pub(crate) fn date(date: Option<NaiveDate>) -> Self {
+		SortKey::Date(date.is_none(), date)
+	}
+}
+
+/// Something that can be shown as a row in a report's table
+pub(crate) trait TableRow {
+	/// Names of all available columns (lower-case, as given on the command line)
+	const COLUMNS: &'static [&'static str];
+
+	/// The text to show in the given column
+	fn cell(&self, column: &str) -> String;
+
+	/// The value to sort by, for the given sort key
+	fn sort_key(&self, by: SortBy) -> SortKey;
+}
+
+/// Check the columns requested (if any) are all available for this type of row
+pub(crate) fn check_columns<T: TableRow>(options: &TableOptions) -> Result<(), String> {
+	if let Some(columns) = &options.columns {
+		if columns.is_empty() {
+			return Err(String::from("No columns given"));
+		}
+		for column in columns {
+			if !T::COLUMNS.contains(&column.to_lowercase().as_str()) {
+				return Err(format!(
+					"Unknown column '{column}' - available columns are: {}",
+					T::COLUMNS.join(",")
+				));
+			}
+		}
+	}
+	Ok(())
+}
+
+/// Sort the rows, pick out the columns, and make a table
+///
+/// The rows are expected to be in the report's default order already. Maximum widths may be
+/// given for any columns, by name.
+pub(crate) fn render<T: TableRow>(
+	mut rows: Vec<T>,
+	default_columns: &[&str],
+	options: &TableOptions,
+	max_widths: &[(&str, u16)],
+) -> Result<String, String> {
+	sort_rows(&mut rows, options);
+	render_sorted(&rows, default_columns, options, max_widths)
+}
+
+/// Put the rows in the order that they'll be shown, if a sort order (or reversal) was requested
+pub(crate) fn sort_rows<T: TableRow>(rows: &mut [T], options: &TableOptions) {
+	if let Some(by) = options.sort {
+		rows.sort_by_cached_key(|row| row.sort_key(by))
+	}
+	if options.reverse {
+		rows.reverse()
+	}
+}
+
+/// Pick out the columns, and make a table, from rows that have already been sorted
+///
+/// This is for reports that need to know the order the rows are shown in (such as to open them).
+pub(crate) fn render_sorted<T: TableRow>(
+	rows: &[T],
+	default_columns: &[&str],
+	options: &TableOptions,
+	max_widths: &[(&str, u16)],
+) -> Result<String, String> {
+	check_columns::<T>(options)?;
+
+	let columns: Vec<String> = match &options.columns {
+		Some(columns) => columns.iter().map(|column| column.to_lowercase()).collect(),
+		None => default_columns
+			.iter()
+			.map(|column| column.to_string())
+			.collect(),
+	};
+
+	let headers: Vec<String> = columns.iter().map(|column| column.to_uppercase()).collect();
+	let widths: HashMap<usize, u16> = columns
+		.iter()
+		.enumerate()
+		.filter_map(|(index, column)| {
+			max_widths
+				.iter()
+				.find(|(name, _)| name == column)
+				.map(|(_, width)| (index, *width))
+		})
+		.collect();
+
+	Ok(make_table(
+		headers.iter().map(String::as_str).collect(),
+		rows.iter()
+			.map(|row| columns.iter().map(|column| row.cell(column)).collect())
+			.collect(),
+		if widths.is_empty() {
+			None
+		} else {
+			Some(widths)
+		},
+	))
+}
+
+/// Show a due date, or say there isn't one
+pub(crate) fn due_cell(due: Option<NaiveDate>) -> String {
+	match due {
+		Some(date) => format!("{date}"),
+		None => String::from("(no date)"),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	struct Row {
+		number: u32,
+		name: &'static str,
+	}
+
+	impl TableRow for Row {
+		const COLUMNS: &'static [&'static str] = &["number", "name"];
+
+		fn cell(&self, column: &str) -> String {
+			match column {
+				"number" => self.number.to_string(),
+				_ => self.name.to_string(),
+			}
+		}
+
+		fn sort_key(&self, by: SortBy) -> SortKey {
+			match by {
+				SortBy::Number => SortKey::Number(self.number),
+				_ => SortKey::Text(self.name.to_string()),
+			}
+		}
+	}
+
+	fn rows() -> Vec<Row> {
+		vec![
+			Row {
+				number: 2,
+				name: "b",
+			},
+			Row {
+				number: 10,
+				name: "a",
+			},
+		]
+	}
+
+	fn lines(table: &str) -> Vec<String> {
+		table.lines().map(|line| line.trim().to_string()).collect()
+	}
+
+	#[test]
+	fn default_order_and_columns() {
+		let table = render(rows(), &["number", "name"], &TableOptions::default(), &[]).unwrap();
+		assert_eq!(
+			lines(&table),
+			vec!["NUMBER  NAME", "2       b", "10      a"]
+		);
+	}
+
+	#[test]
+	fn sorted_reversed_and_selected() {
+		let options = TableOptions {
+			sort: Some(SortBy::Number),
+			reverse: true,
+			columns: Some(vec![String::from("Name")]),
+		};
+		let table = render(rows(), &["number", "name"], &options, &[]).unwrap();
+		assert_eq!(lines(&table), vec!["NAME", "a", "b"]);
+	}
+
+	#[test]
+	fn sorted_in_shown_order() {
+		let options = TableOptions {
+			sort: Some(SortBy::Repo),
+			..Default::default()
+		};
+		let mut rows = rows();
+		sort_rows(&mut rows, &options);
+		assert_eq!(
+			rows.iter().map(|row| row.number).collect::<Vec<_>>(),
+			vec![10, 2]
+		);
+	}
+
+	#[test]
+	fn unknown_column() {
+		let options = TableOptions {
+			columns: Some(vec![String::from("nope")]),
+			..Default::default()
+		};
+		assert!(check_columns::<Row>(&options).is_err());
+	}
+
+	#[test]
+	fn sort_names() {
+		assert_eq!(SortBy::from_str("updated"), Ok(SortBy::Updated));
+		assert_eq!(SortBy::Assignee.to_string(), "assignee");
+		assert!(SortBy::from_str("nope").is_err());
+	}
+
+	#[test]
+	fn undated_last() {
+		let early = SortKey::date(NaiveDate::from_ymd_opt(2024, 1, 1));
+		let late = SortKey::date(NaiveDate::from_ymd_opt(2024, 2, 1));
+		let undated = SortKey::date(None);
+		assert!(early < late);
+		assert!(late < undated);
+	}
+}